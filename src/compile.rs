@@ -0,0 +1,124 @@
+//! Compile verification of generated files.
+//!
+//! This invokes a C compiler in syntax-only mode against the generated
+//! output. It catches regex/codegen bugs which would otherwise only show up
+//! once the user tries to build their project.
+
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The command line flavour of a discovered compiler.
+#[derive(Debug, PartialEq)]
+enum Flavor {
+    /// gcc, clang and anything else accepting `cc`-style flags
+    Cc,
+
+    /// cl.exe
+    Msvc
+}
+
+/// The language a file is compiled as.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lang {
+    C,
+    Cpp
+}
+
+/// A C compiler invocation discovered via the environment.
+#[derive(Debug)]
+pub struct Compiler {
+    program: String,
+    args: Vec<String>,
+    flavor: Flavor,
+    lang: Lang
+}
+
+impl Compiler {
+    /// Find a compiler from `CC` (`CXX` for C++), falling back to `cl` on
+    /// Windows and `cc` (`c++`) everywhere else.
+    ///
+    /// `CC` may contain extra arguments (e.g. `gcc -m32`) which are passed
+    /// through before our own.
+    pub fn discover(lang: Lang) -> Compiler {
+        let (var, default) = match lang {
+            Lang::C => ("CC", "cc"),
+            Lang::Cpp => ("CXX", "c++")
+        };
+        let cc = match env::var(var) {
+            Ok(ref cc) if !cc.trim().is_empty() => cc.clone(),
+            _ => if cfg!(windows) { "cl".to_string() } else { default.to_string() }
+        };
+
+        let mut words = cc.split_whitespace().map(|s| s.to_string());
+        let program = words.next().unwrap();
+        let args = words.collect();
+
+        let flavor = match Path::new(&program).file_stem().and_then(|s| s.to_str()) {
+            Some("cl") => Flavor::Msvc,
+            _ => Flavor::Cc
+        };
+
+        Compiler {
+            program,
+            args,
+            flavor,
+            lang
+        }
+    }
+
+    /// Check that `file` compiles as the language the compiler was
+    /// discovered for.
+    ///
+    /// Returns `Ok(false)` without doing anything if the compiler could not
+    /// be found, `Ok(true)` if compilation succeeded and an error containing
+    /// the compiler output otherwise.
+    pub fn check(&self, file: &Path, include_dirs: &[PathBuf], defines: &[&str]) -> io::Result<bool> {
+        let mut cmd = Command::new(&self.program);
+        cmd.args(&self.args);
+
+        match self.flavor {
+            Flavor::Cc => {
+                cmd.arg("-fsyntax-only").arg("-x").arg(if self.lang == Lang::Cpp { "c++" } else { "c" });
+                for dir in include_dirs {
+                    cmd.arg("-I").arg(dir);
+                }
+                for define in defines {
+                    cmd.arg(format!("-D{}", define));
+                }
+            }
+
+            Flavor::Msvc => {
+                cmd.arg("/nologo").arg("/Zs").arg(if self.lang == Lang::Cpp { "/TP" } else { "/TC" });
+                for dir in include_dirs {
+                    cmd.arg("/I").arg(dir);
+                }
+                for define in defines {
+                    cmd.arg(format!("/D{}", define));
+                }
+            }
+        }
+
+        cmd.arg(file);
+
+        let output = match cmd.output() {
+            Ok(output) => output,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e)
+        };
+
+        if output.status.success() {
+            Ok(true)
+        }
+        else {
+            Err(io::Error::new(io::ErrorKind::Other, format!(
+                "{} failed to compile ({}):\n{}{}",
+                file.display(),
+                self.program,
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            )))
+        }
+    }
+}
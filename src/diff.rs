@@ -0,0 +1,288 @@
+//! Unified diffs of generated files.
+//!
+//! This lets users preview what regenerating would change in their existing
+//! files (e.g. those from upstream gl3w) before overwriting them. Lines are
+//! compared with Myers' algorithm.
+//!
+//! Patches can also be applied, for users layering local changes onto the
+//! registry headers.
+
+use std::cmp;
+
+/// Differences further apart than this are cheaper to show as the whole
+/// file being replaced than to search for.
+const MAX_EDITS: usize = 2048;
+
+/// Lines of unchanged context around each change.
+const CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit<'a> {
+    Same(&'a str),
+    Delete(&'a str),
+    Insert(&'a str)
+}
+
+/// The shortest list of edits turning `a` into `b`, or `None` if it is
+/// longer than `MAX_EDITS`.
+fn edits<'a>(a: &[&'a str], b: &[&'a str]) -> Option<Vec<Edit<'a>>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = cmp::min((n + m) as usize, MAX_EDITS) as isize;
+
+    // `v[k + offset]` is the furthest `x` reached on diagonal `k = x - y`.
+    // The diagonals round `d` reads from are copied before each round to
+    // walk back through.
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    let mut trace = Vec::new();
+
+    let mut found = false;
+    'search: for d in 0..max + 1 {
+        trace.push(v[(offset - d - 1) as usize..(offset + d + 2) as usize].to_vec());
+
+        let mut k = -d;
+        while k <= d {
+            let i = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[i - 1] < v[i + 1]) { v[i + 1] } else { v[i - 1] + 1 };
+            let mut y = x - k;
+
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[i] = x;
+
+            if x >= n && y >= m {
+                found = true;
+                break 'search;
+            }
+            k += 2;
+        }
+    }
+    if !found {
+        return None;
+    }
+
+    let mut result = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let i = (k + d + 1) as usize;
+        let prev_k = if k == -d || (k != d && v[i - 1] < v[i + 1]) { k + 1 } else { k - 1 };
+        let prev_x = v[(prev_k + d + 1) as usize];
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            x -= 1;
+            y -= 1;
+            result.push(Edit::Same(a[x as usize]));
+        }
+
+        if d > 0 {
+            if x == prev_x {
+                y -= 1;
+                result.push(Edit::Insert(b[y as usize]));
+            }
+            else {
+                x -= 1;
+                result.push(Edit::Delete(a[x as usize]));
+            }
+        }
+    }
+
+    result.reverse();
+    Some(result)
+}
+
+/// A unified diff from `old` (named `old_name`) to `new`, empty if they
+/// have the same lines.
+pub fn unified(old_name: &str, new_name: &str, old: &str, new: &str) -> String {
+    let a = old.lines().collect::<Vec<_>>();
+    let b = new.lines().collect::<Vec<_>>();
+
+    let edits = edits(&a, &b).unwrap_or_else(|| {
+        a.iter().map(|l| Edit::Delete(l)).chain(b.iter().map(|l| Edit::Insert(l))).collect()
+    });
+
+    let changes = edits.iter().enumerate()
+                       .filter(|&(_, e)| match *e { Edit::Same(_) => false, _ => true })
+                       .map(|(i, _)| i)
+                       .collect::<Vec<_>>();
+    if changes.is_empty() {
+        return String::new();
+    }
+
+    // Changes close enough to share context go in the same hunk
+    let mut hunks = Vec::new();
+    let mut start = changes[0];
+    let mut last = changes[0];
+    for &i in &changes[1..] {
+        if i - last > 2 * CONTEXT {
+            hunks.push((start, last));
+            start = i;
+        }
+        last = i;
+    }
+    hunks.push((start, last));
+
+    let mut out = format!("--- {}\n+++ {}\n", old_name, new_name);
+    for (first, last) in hunks {
+        let from = first.saturating_sub(CONTEXT);
+        let to = cmp::min(last + CONTEXT + 1, edits.len());
+
+        let before = &edits[..from];
+        let old_start = before.iter().filter(|e| match **e { Edit::Insert(_) => false, _ => true }).count();
+        let new_start = before.iter().filter(|e| match **e { Edit::Delete(_) => false, _ => true }).count();
+
+        let hunk = &edits[from..to];
+        let old_len = hunk.iter().filter(|e| match **e { Edit::Insert(_) => false, _ => true }).count();
+        let new_len = hunk.iter().filter(|e| match **e { Edit::Delete(_) => false, _ => true }).count();
+
+        // An empty range is numbered by the line before it
+        out.push_str(&format!("@@ -{},{} +{},{} @@\n",
+                              if old_len == 0 { old_start } else { old_start + 1 }, old_len,
+                              if new_len == 0 { new_start } else { new_start + 1 }, new_len));
+
+        for e in hunk {
+            match *e {
+                Edit::Same(l) => out.push_str(&format!(" {}\n", l)),
+                Edit::Delete(l) => out.push_str(&format!("-{}\n", l)),
+                Edit::Insert(l) => out.push_str(&format!("+{}\n", l))
+            }
+        }
+    }
+
+    out
+}
+
+/// A hunk of a unified diff, with the lines it expects and replaces them
+/// with.
+struct Hunk<'a> {
+    /// Where `old` starts in the original, counting from 0
+    start: usize,
+    old: Vec<&'a str>,
+    new: Vec<&'a str>
+}
+
+/// Parse the `-start,len` or `+start,len` range of a hunk header.
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    let mut parts = range[1..].splitn(2, ',');
+    let start = parts.next().and_then(|s| s.parse().ok());
+    let len = parts.next().map_or(Some(1), |s| s.parse().ok());
+    match (start, len) {
+        (Some(start), Some(len)) => Some((start, len)),
+        _ => None
+    }
+}
+
+/// The hunks of `patch`. Anything outside a hunk, such as the file names,
+/// is ignored.
+fn hunks<'a>(patch: &'a str) -> Result<Vec<Hunk<'a>>, String> {
+    let mut hunks = Vec::new();
+    let mut lines = patch.lines().enumerate();
+
+    while let Some((n, line)) = lines.next() {
+        if !line.starts_with("@@ ") {
+            continue;
+        }
+
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let ranges = if words.len() >= 3 && words[1].starts_with('-') && words[2].starts_with('+') {
+            parse_range(words[1]).and_then(|old| parse_range(words[2]).map(|new| (old, new)))
+        }
+        else {
+            None
+        };
+        let ((old_start, mut old_len), (_, mut new_len)) = match ranges {
+            Some(ranges) => ranges,
+            None => return Err(format!("line {}: malformed hunk header", n + 1))
+        };
+
+        // An empty range is numbered by the line before it
+        let mut hunk = Hunk {
+            start: if old_len == 0 { old_start } else { old_start.saturating_sub(1) },
+            old: Vec::new(),
+            new: Vec::new()
+        };
+        while old_len > 0 || new_len > 0 {
+            let line = match lines.next() {
+                Some((_, line)) => line,
+                None => return Err(format!("line {}: hunk ends early", n + 1))
+            };
+
+            // Some editors strip the space from empty context lines
+            let (kind, text) = if line.is_empty() { (' ', "") } else { (line.as_bytes()[0] as char, &line[1..]) };
+            match kind {
+                ' ' if old_len > 0 && new_len > 0 => {
+                    hunk.old.push(text);
+                    hunk.new.push(text);
+                    old_len -= 1;
+                    new_len -= 1;
+                }
+                '-' if old_len > 0 => {
+                    hunk.old.push(text);
+                    old_len -= 1;
+                }
+                '+' if new_len > 0 => {
+                    hunk.new.push(text);
+                    new_len -= 1;
+                }
+                '\\' => {}
+                _ => return Err(format!("line {}: hunk does not match its header", n + 1))
+            }
+        }
+        hunks.push(hunk);
+    }
+
+    if hunks.is_empty() {
+        return Err("no hunks found".to_string());
+    }
+    Ok(hunks)
+}
+
+/// Apply the unified diff `patch` to `text`.
+///
+/// As with `patch`, a hunk whose lines have moved (e.g. after the header
+/// was updated upstream) is applied where they are found nearest to the
+/// line it names. There is no fuzz though, every context and removed line
+/// must match exactly.
+pub fn apply(text: &str, patch: &str) -> Result<String, String> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let mut patched = Vec::new();
+    let mut pos = 0;
+
+    for (i, hunk) in hunks(patch)?.into_iter().enumerate() {
+        let matches = |at: usize| at + hunk.old.len() <= lines.len() && lines[at..at + hunk.old.len()] == hunk.old[..];
+
+        // Search outwards from where the hunk says it goes, but never
+        // before the end of the previous one
+        let expected = cmp::max(hunk.start, pos);
+        let mut at = None;
+        for delta in 0..lines.len() + 1 {
+            if matches(expected + delta) {
+                at = Some(expected + delta);
+                break;
+            }
+            if delta > 0 && expected >= pos + delta && matches(expected - delta) {
+                at = Some(expected - delta);
+                break;
+            }
+        }
+
+        let at = match at {
+            Some(at) => at,
+            None => return Err(format!("hunk {} does not apply", i + 1))
+        };
+        patched.extend_from_slice(&lines[pos..at]);
+        patched.extend_from_slice(&hunk.new);
+        pos = at + hunk.old.len();
+    }
+    patched.extend_from_slice(&lines[pos..]);
+
+    let mut patched = patched.join("\n");
+    if text.ends_with('\n') {
+        patched.push('\n');
+    }
+    Ok(patched)
+}
@@ -0,0 +1,5477 @@
+//! gl3w-native
+//! ===========
+//!
+//! This is a port of [gl3w](https://github.com/skaslev/gl3w) to Rust, as
+//! the `gl3w-native` binary and as a library for generating loaders from
+//! other programs.
+//!
+//! This removes the python dependency in favour of a statically linked
+//! executable (which is hopefully fairly small).
+//!
+//! Rust is chosen simply because it provides easy cross-platform support and
+//! has no real runtime.
+
+// `dyn` needs a newer rustc than we support
+#![allow(unknown_lints, bare_trait_objects)]
+
+extern crate flate2;
+extern crate hyper;
+extern crate num_cpus;
+extern crate regex;
+extern crate time;
+
+mod compile;
+mod diff;
+mod sha256;
+#[cfg(test)]
+mod tests;
+
+use hyper::client::{Pool, RedirectPolicy};
+use hyper::header::{ContentLength, ContentType, ETag, EntityTag, Headers, IfNoneMatch, Location};
+use hyper::method::Method;
+use hyper::net::{HttpsConnector, Openssl};
+use hyper::mime::{Mime, SubLevel, TopLevel};
+use std::cmp;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Write, Read};
+use std::fmt;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{mpsc, Arc, Mutex};
+#[allow(deprecated)]
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// An OpenGL function to load.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Proc {
+    /// The OpenGL name, e.g. `glCullFace`
+    pub id: String,
+
+    /// The gl3w function pointer, e.g. `gl3wCullFace`
+    pub var: String,
+
+    /// The function pointer type, e.g. `PFNGLCULLFACEPROC`
+    pub pfn: String,
+
+    /// The return type and parameter list of the `GLAPI` prototype, if one
+    /// was declared
+    proto: Option<(String, String)>,
+
+    /// The innermost `GL_VERSION_*` or extension block declaring this
+    block: Option<String>,
+
+    /// The header declaring this, e.g. `glcorearb.h`, if it could be told
+    /// from its include guard
+    source: Option<String>,
+
+    /// Where the declaration starts in the parsed header
+    offset: usize
+}
+
+impl Proc {
+    /// The function named `id`, e.g. `glClear`.
+    ///
+    /// # Panics
+    ///
+    /// If `id` does not start with `gl`.
+    pub fn new(id: &str) -> Proc {
+        assert!(id.starts_with("gl"), "`{}` is not an OpenGL function name, which start with `gl`", id);
+        Proc {
+            id: id.to_string(),
+            var: "gl3w".to_string() + &id[2..],
+            pfn: "PFN".to_string() + &id.to_uppercase() + "PROC",
+            proto: None,
+            block: None,
+            source: None,
+            offset: 0
+        }
+    }
+
+    /// Whether this was declared by glcorearb.h, which is preferred over
+    /// other headers declaring the same function.
+    fn is_core(&self) -> bool {
+        self.source.as_ref().map_or(false, |s| s == "glcorearb.h")
+    }
+
+    fn with_proto(id: &str, ret: &str, params: &str) -> Proc {
+        Proc {
+            proto: Some((ret.to_string(), params.to_string())),
+            ..Proc::new(id)
+        }
+    }
+}
+
+/// The Khronos registry, which serves each header under
+/// `<registry>/api/<dir>/`. See `registry_header`.
+const URL_REGISTRY: &str = "https://registry.khronos.org";
+
+/// The canonical location of glcorearb.h.
+const URL_GLCOREARB: &str = "https://registry.khronos.org/OpenGL/api/GL/glcorearb.h";
+
+/// Where glcorearb.h was hosted before the registry moved to khronos.org.
+const URL_GLCOREARB_OLD: &str = "https://www.opengl.org/registry/api/GL/glcorearb.h";
+
+/// The canonical location of glext.h, needed for the compatibility profile.
+const URL_GLEXT: &str = "https://registry.khronos.org/OpenGL/api/GL/glext.h";
+
+/// The raw GitHub mirror of the registry, which serves any revision of the
+/// headers under `<ref>/api/GL/`.
+const URL_REGISTRY_GITHUB: &str = "https://raw.githubusercontent.com/KhronosGroup/OpenGL-Registry";
+
+/// The most bytes downloaded for a single header unless `--max-size` is
+/// given. glext.h, the largest, is well under a megabyte.
+const DEFAULT_MAX_SIZE: u64 = 50 * 1024 * 1024;
+
+/// A `GL_*` constant and its (unparsed) value.
+#[derive(Debug, Eq, PartialEq)]
+struct Enum(String, String);
+
+/// A type declaration from glcorearb.h: the name it introduces and the full
+/// declaration text.
+///
+/// Forward `struct` declarations are included as these are needed by some
+/// prototypes just like typedefs are.
+#[derive(Debug)]
+struct Typedef(String, String);
+
+/// The platform code used by the generated loader to resolve functions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Loader {
+    /// WGL, CGL or GLX depending on the target platform, as upstream gl3w
+    Native,
+
+    /// `SDL_GL_GetProcAddress` from SDL2
+    Sdl2,
+
+    /// `glfwGetProcAddress` from GLFW 3
+    Glfw,
+
+    /// The platform's `GetProcAddress`, falling back to looking the symbol
+    /// up in a `dlopen`ed libGL
+    Dlopen,
+
+    /// Windows only, `wglGetProcAddress` falling back to the exports of
+    /// opengl32.dll
+    Win32,
+
+    /// macOS only, `dlsym` on the OpenGL framework
+    Macos,
+
+    /// `emscripten_GetProcAddress` for WebGL in the browser
+    Emscripten
+}
+
+impl Loader {
+    fn from_name(name: &str) -> Option<Loader> {
+        match name {
+            "native" => Some(Loader::Native),
+            "sdl2" => Some(Loader::Sdl2),
+            "glfw" => Some(Loader::Glfw),
+            "dlopen" => Some(Loader::Dlopen),
+            "win32" => Some(Loader::Win32),
+            "macos" => Some(Loader::Macos),
+            "emscripten" => Some(Loader::Emscripten),
+            _ => None
+        }
+    }
+
+    /// The definitions of `open_libgl`, `close_libgl` and `get_proc`.
+    fn template(&self) -> &'static str {
+        match *self {
+            Loader::Native => include_str!("template/loader/native.c"),
+            Loader::Sdl2 => include_str!("template/loader/sdl2.c"),
+            Loader::Glfw => include_str!("template/loader/glfw.c"),
+            Loader::Dlopen => include_str!("template/loader/dlopen.c"),
+            Loader::Win32 => include_str!("template/loader/win32.c"),
+            Loader::Macos => include_str!("template/loader/macos.c"),
+            Loader::Emscripten => include_str!("template/loader/emscripten.c")
+        }
+    }
+
+    /// The definitions of `create_context` and `destroy_context` for the
+    /// smoke test, which make a new context current and tear it down.
+    fn smoke_template(&self) -> &'static str {
+        match *self {
+            Loader::Native | Loader::Dlopen | Loader::Win32 | Loader::Macos => include_str!("template/smoke/native.c"),
+            Loader::Sdl2 => include_str!("template/smoke/sdl2.c"),
+            // Emscripten ships a GLFW port which creates a WebGL context
+            Loader::Glfw | Loader::Emscripten => include_str!("template/smoke/glfw.c")
+        }
+    }
+
+    /// The definition of `libgl_opened`, which returns non-zero if
+    /// `open_libgl` found the library.
+    fn opened_template(&self) -> &'static str {
+        match *self {
+            Loader::Native => include_str!("template/opened/native.c"),
+            Loader::Sdl2 => include_str!("template/opened/sdl2.c"),
+            Loader::Glfw => include_str!("template/opened/glfw.c"),
+            Loader::Dlopen => include_str!("template/opened/dlopen.c"),
+            Loader::Win32 => include_str!("template/opened/win32.c"),
+            Loader::Macos => include_str!("template/opened/macos.c"),
+            Loader::Emscripten => include_str!("template/opened/emscripten.c")
+        }
+    }
+
+    /// The definition of `has_context`, which returns non-zero if a GL
+    /// context is current. This may only be called between `open_libgl` and
+    /// `close_libgl`.
+    fn context_template(&self) -> &'static str {
+        match *self {
+            Loader::Native => include_str!("template/context/native.c"),
+            Loader::Sdl2 => include_str!("template/context/sdl2.c"),
+            Loader::Glfw => include_str!("template/context/glfw.c"),
+            Loader::Dlopen => include_str!("template/context/dlopen.c"),
+            Loader::Win32 => include_str!("template/context/win32.c"),
+            Loader::Macos => include_str!("template/context/macos.c"),
+            Loader::Emscripten => include_str!("template/context/emscripten.c")
+        }
+    }
+}
+
+/// The format `--manifest` is written in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ManifestFormat {
+    /// The source and one function per line, the default
+    Text,
+
+    Json,
+
+    Toml
+}
+
+impl ManifestFormat {
+    fn from_name(name: &str) -> Option<ManifestFormat> {
+        match name {
+            "text" => Some(ManifestFormat::Text),
+            "json" => Some(ManifestFormat::Json),
+            "toml" => Some(ManifestFormat::Toml),
+            _ => None
+        }
+    }
+}
+
+/// Which generated file `--stdout` writes to stdout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Artifact {
+    /// gl3w.h, or the single file
+    Header,
+
+    /// gl3w.c
+    Source
+}
+
+impl Artifact {
+    fn from_name(name: &str) -> Option<Artifact> {
+        match name {
+            "header" => Some(Artifact::Header),
+            "source" => Some(Artifact::Source),
+            _ => None
+        }
+    }
+}
+
+/// Which field the generated procs are ordered by.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortKey {
+    /// The OpenGL function name, the default
+    Id,
+
+    /// The `PFN*PROC` typedef name
+    Pfn,
+
+    /// The generated variable name
+    Var,
+
+    /// The order declared in the header, as upstream gl3w does
+    Source
+}
+
+impl SortKey {
+    fn from_name(name: &str) -> Option<SortKey> {
+        match name {
+            "id" => Some(SortKey::Id),
+            "pfn" => Some(SortKey::Pfn),
+            "var" => Some(SortKey::Var),
+            "source" => Some(SortKey::Source),
+            _ => None
+        }
+    }
+}
+
+/// Which OpenGL profile the generated header targets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Profile {
+    /// Only glcorearb.h
+    Core,
+
+    /// The system gl.h plus glext.h
+    Compatibility,
+
+    /// OpenGL ES 3 from gl3.h, as WebGL 2 exposes
+    Gles3
+}
+
+impl Profile {
+    fn from_name(name: &str) -> Option<Profile> {
+        match name {
+            "core" => Some(Profile::Core),
+            "compatibility" => Some(Profile::Compatibility),
+            "gles3" => Some(Profile::Gles3),
+            _ => None
+        }
+    }
+
+    /// The OpenGL header includes emitted at the top of gl3w.h.
+    fn template(&self) -> &'static str {
+        match *self {
+            Profile::Core => include_str!("template/profile/core.h"),
+            Profile::Compatibility => include_str!("template/profile/compatibility.h"),
+            Profile::Gles3 => include_str!("template/profile/gles3.h")
+        }
+    }
+
+    /// The includes for `--cross-platform-includes`, which pick each
+    /// platform's own location of the system headers.
+    fn cross_template(&self) -> &'static str {
+        match *self {
+            Profile::Core => include_str!("template/profile/core.cross.h"),
+            Profile::Compatibility => include_str!("template/profile/compatibility.cross.h"),
+            Profile::Gles3 => include_str!("template/profile/gles3.h")
+        }
+    }
+
+    /// The header we parse, which the template includes.
+    fn header(&self) -> &'static str {
+        match *self {
+            Profile::Core => "glcorearb.h",
+            Profile::Compatibility => "glext.h",
+            Profile::Gles3 => "gl3.h"
+        }
+    }
+
+    /// The directory the template includes the header from.
+    fn include_dir(&self) -> &'static str {
+        match *self {
+            Profile::Core | Profile::Compatibility => "GL",
+            Profile::Gles3 => "GLES3"
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Gl3wPath {
+    /// Represents a single header version
+    Single(PathBuf),
+
+    /// Represents a *.h/*.c file pair
+    Separate(PathBuf, PathBuf)
+}
+
+/// What to do with the parsed header.
+#[derive(Debug, PartialEq)]
+enum Command {
+    /// Generate the gl3w files, the default
+    Generate,
+
+    /// Print the extensions declared by the header
+    ListExtensions,
+
+    /// Print the functions which would be generated
+    ListProcs,
+
+    /// Only download the header into the cache
+    FetchOnly,
+
+    /// Print how generating would change the existing gl3w files
+    DryDiff,
+
+    /// Add the functions of some extensions to the existing gl3w files
+    AppendExtensions,
+
+    /// Check the existing gl3w files match what would be generated
+    Verify,
+
+    /// Check the header urls can be reached, without downloading them
+    CheckUrl,
+
+    /// Only check the header urls return `200 OK`, for CI
+    HeadValidate,
+
+    /// Compare the functions in an existing gl3w.h against the header
+    Audit,
+
+    /// Generate only gl3w.c, for the functions an existing gl3w.h declares
+    LoaderOnly,
+
+    /// Print our version and nothing else
+    PrintVersion,
+
+    /// Write the built-in templates to a directory
+    DumpTemplates,
+
+    /// Print the files generating would write
+    ListOutputs
+}
+
+/// An extra file to generate alongside the gl3w files.
+#[derive(Debug)]
+enum OutputTarget {
+    /// The extracted `GL_*` constants, as JSON if the path ends in `.json`
+    /// and a C header otherwise
+    Enums(PathBuf),
+
+    /// The typedefs used by the generated procs
+    Typedefs(PathBuf),
+
+    /// A program checking the generated loader works
+    SmokeTest(PathBuf),
+
+    /// A compilation database for building the loader
+    CompileCommands(PathBuf),
+
+    /// The source and generated functions, for `--changelog`
+    Manifest(PathBuf),
+
+    /// The generated functions as JSON
+    Procs(PathBuf),
+
+    /// A JSON Schema for the `Procs` output
+    ProcsSchema(PathBuf)
+}
+
+impl OutputTarget {
+    fn path(&self) -> &PathBuf {
+        match *self {
+            OutputTarget::Enums(ref path) |
+            OutputTarget::Typedefs(ref path) |
+            OutputTarget::SmokeTest(ref path) |
+            OutputTarget::CompileCommands(ref path) |
+            OutputTarget::Manifest(ref path) |
+            OutputTarget::Procs(ref path) |
+            OutputTarget::ProcsSchema(ref path) => path
+        }
+    }
+}
+
+/// Options for downloading headers, kept apart so they can be handed to the
+/// download threads.
+#[derive(Debug, Default, Clone, Copy)]
+struct Fetch {
+    /// Bypass the cache and get all files remotely
+    no_cache: bool,
+
+    /// Warn if a cached header no longer matches the remote copy
+    check_stale: bool,
+
+    /// Accept a response served as HTML
+    allow_html: bool,
+
+    /// Only use a cached header if the server reports the same size (and
+    /// ETag) for it
+    revalidate: bool,
+
+    /// Keep downloaded headers in memory only, leaving the cache untouched
+    no_write: bool,
+
+    /// Use the cached header if downloading a fresh copy fails
+    stale_on_error: bool,
+
+    /// The most bytes to download for a header, if not `DEFAULT_MAX_SIZE`
+    max_size: Option<u64>,
+
+    /// Don't print warnings, as for `Gl3wExec::quiet`
+    quiet: bool
+}
+
+/// Hooks into a run, for embedding gl3w-native behind a progress UI instead
+/// of parsing what it prints. Each does nothing unless implemented.
+///
+/// Extra headers are downloaded in parallel, so the download hooks may be
+/// called from several threads at once.
+pub trait Gl3wObserver: fmt::Debug + Send + Sync {
+    /// `bytes` of `url` have been downloaded so far, out of `total` if the
+    /// server sent a length.
+    fn on_download_progress(&self, _url: &str, _bytes: u64, _total: Option<u64>) {}
+
+    /// All `bytes` of `url` have been downloaded.
+    fn on_download_finished(&self, _url: &str, _bytes: u64) {}
+
+    /// The header was parsed, finding `count` functions to generate.
+    fn on_parsed(&self, _count: usize) {}
+
+    /// `path` was generated.
+    fn on_file_written(&self, _path: &Path) {}
+}
+
+/// The `--progress` observer, showing downloads on stderr.
+#[derive(Debug)]
+struct ProgressPrinter;
+
+impl Gl3wObserver for ProgressPrinter {
+    fn on_download_progress(&self, url: &str, bytes: u64, total: Option<u64>) {
+        match total {
+            Some(total) => eprint!("\rdownloading {}: {}/{} KiB", url, bytes / 1024, total / 1024),
+            None => eprint!("\rdownloading {}: {} KiB", url, bytes / 1024)
+        }
+    }
+
+    fn on_download_finished(&self, url: &str, bytes: u64) {
+        eprintln!("\rdownloaded {}: {} KiB", url, bytes / 1024);
+    }
+}
+
+/// An `ExecEngine` will run the required commands based on the options it
+/// was initialized with.
+#[derive(Debug)]
+pub struct Gl3wExec {
+    /// What to run
+    command: Command,
+
+    /// Where we should get the glcorearb.h file from.
+    url_glcorearb: String,
+
+    /// Where the glcorearb file should be located at
+    path_glcorearb: PathBuf,
+
+    /// Where we should get the glext.h file from, which replaces glcorearb.h
+    /// for the compatibility profile
+    url_glext: String,
+
+    /// Where the glext file should be located at
+    path_glext: PathBuf,
+
+    /// Where the gl3w.* files should be stored at
+    path_gl3w: Gl3wPath,
+
+    /// How headers are downloaded and cached
+    fetch: Fetch,
+
+    /// Shared by every download so connections are kept alive between them
+    client: Arc<Http>,
+
+    /// Check the generated files compile with the system C compiler
+    verify_compile: bool,
+
+    /// Extra files to generate after the gl3w files, in order
+    outputs: Vec<OutputTarget>,
+
+    /// Leave the generation date out of the metadata comment
+    no_timestamp: bool,
+
+    /// Make every output the same on each run, recording no dates but
+    /// `source_date_epoch` and no timings
+    reproducible: bool,
+
+    /// The `SOURCE_DATE_EPOCH` to record instead of the current time
+    source_date_epoch: Option<i64>,
+
+    /// Only dump the constants from blocks declaring a generated function
+    prune_enums: bool,
+
+    /// Comment each function pointer with the version or extension which
+    /// introduced it
+    annotate_versions: bool,
+
+    /// Comment each function pointer with the header declaring it
+    annotate_source: bool,
+
+    /// Declare the function pointers in a commented section per version
+    group_by_version: bool,
+
+    /// Fail once any warning has been given
+    fail_on_warning: bool,
+
+    /// Append the functions added and removed since the last `--manifest`
+    /// here
+    changelog: Option<PathBuf>,
+
+    /// Write a JSON summary of the run here
+    stats: Option<PathBuf>,
+
+    /// Write a Makefile rule from the generated files to their inputs here
+    depfile: Option<PathBuf>,
+
+    /// Write the functions left out by the filters here
+    list_filtered: Option<PathBuf>,
+
+    /// Write a CMake package of the generated files to this directory
+    cmake_package: Option<PathBuf>,
+
+    /// Write this file to stdout instead of its path
+    stdout: Option<Artifact>,
+
+    /// Print how long fetching, parsing and generating took
+    bench: bool,
+
+    /// Skip generating if the header is the same as on the last run
+    if_changed: bool,
+
+    /// Skip the files a failed run already wrote
+    resume: bool,
+
+    /// Guard gl3w.h with `#pragma once` instead of `#ifndef`
+    pragma_once: bool,
+
+    /// Local headers to read instead of fetching `url_glcorearb`
+    inputs: Vec<PathBuf>,
+
+    /// Additional headers to download and parse after glcorearb.h
+    extra_headers: Vec<String>,
+
+    /// How many jobs (downloads, compiles) may run at once
+    threads: usize,
+
+    /// Variable names to use instead of `gl3w*` for some functions, by
+    /// function name
+    name_map: HashMap<String, String>,
+
+    /// Find functions with this instead of the `GLAPI` prototype pattern.
+    /// The first group captures the function name.
+    proc_regex: Option<regex::Regex>,
+
+    /// Only generate functions whose name matches one of these, if any
+    matches: Vec<regex::Regex>,
+
+    /// Never generate functions whose name matches one of these
+    exclude_matches: Vec<regex::Regex>,
+
+    /// Only generate these functions, as referenced by a binary
+    used: Option<HashSet<String>>,
+
+    /// Only generate the functions of these extensions, along with the core
+    /// ones
+    extensions: Option<HashSet<String>>,
+
+    /// Only generate functions introduced by this OpenGL version or later
+    since: Option<(u32, u32)>,
+
+    /// Only generate extension functions from these vendors, e.g. `NV`
+    vendors: Vec<String>,
+
+    /// Never generate functions removed from the core profile
+    no_deprecated: bool,
+
+    /// The order functions are generated in
+    sort_by: SortKey,
+
+    /// Ignore case when sorting by name
+    sort_case_insensitive: bool,
+
+    /// The column names are padded to in declarations and `#define`s, if
+    /// not the usual widths
+    align: Option<usize>,
+
+    /// Write `\r\n` line endings instead of `\n`
+    crlf: bool,
+
+    /// Strip comments, blank lines and alignment from generated files
+    minify: bool,
+
+    /// What the function pointers are named after, e.g. `gl3w` for
+    /// `gl3wCullFace`
+    prefix: String,
+
+    /// Assert function pointers are loaded when called through the OpenGL
+    /// names
+    assert_loaded: bool,
+
+    /// Wrap the OpenGL names to log each call when `GL3W_TRACE` is defined
+    trace: bool,
+
+    /// The macro exporting gl3w from a shared library, e.g. `GL3W_API`
+    export_macro: Option<String>,
+
+    /// What `--manifest` is written as
+    manifest_format: ManifestFormat,
+
+    /// Leave the `extern "C"` block for C++ out of the headers
+    no_extern_c: bool,
+
+    /// Told about the progress of a run
+    observer: Option<Arc<Gl3wObserver>>,
+
+    /// Record which functions loaded, for `gl3wProcLoaded`
+    load_bitmap: bool,
+
+    /// Generate `gl3wSelfTest`, the fraction of functions which loaded
+    selftest: bool,
+
+    /// Only load extension functions once `gl3wLoadExtension` is called for
+    /// their extension
+    lazy_extensions: bool,
+
+    /// Emit `enum gl3w_proc_index` without `--load-bitmap`
+    index_enum: bool,
+
+    /// Generate `gl3w_proc_names`, the name of each indexed function
+    proc_names: bool,
+
+    /// Other prefixes to `#define` each function pointer under, for code
+    /// still using an old prefix
+    compat_prefixes: Vec<String>,
+
+    /// The calling convention macro for our own function pointer types,
+    /// instead of the header's `APIENTRYP` ones
+    calling_convention: Option<String>,
+
+    /// The generated header `audit` and `--emit-loader-only` read, if not
+    /// the usual one
+    existing_header: Option<PathBuf>,
+
+    /// Where `--dump-templates` writes the templates
+    template_dump_dir: Option<PathBuf>,
+
+    /// A unified diff applied to the header before it is parsed
+    patch: Option<PathBuf>,
+
+    /// Extensions whose functions `--append-extension` adds
+    append_extensions: Vec<String>,
+
+    /// Explain decisions made while parsing
+    verbose: bool,
+
+    /// Print nothing on success, not even warnings
+    quiet: bool,
+
+    /// The number of warnings given so far, counted even when they are not
+    /// printed. The download threads share it.
+    warnings: Arc<AtomicUsize>,
+
+    /// Generate output even if no functions were found
+    allow_empty: bool,
+
+    /// Fail unless exactly this many functions are generated
+    expect_count: Option<usize>,
+
+    /// How the generated loader resolves function pointers
+    loader: Loader,
+
+    /// The library the `dlopen` loader opens, instead of the platform default
+    libgl_name: Option<String>,
+
+    /// Make `gl3wInit` fail unless a GL context is current
+    require_context: bool,
+
+    /// Make `gl3wInit` do nothing once it has succeeded, and generate
+    /// `gl3wReinit` to load again
+    idempotent_init: bool,
+
+    /// Make gl3wInit count its callers and generate gl3wShutdown to release
+    /// them, and gl3wReinit to load again
+    refcount_init: bool,
+
+    /// The language of the generated source, C++ adding the `gl3w::Loader`
+    /// class
+    lang: compile::Lang,
+
+    /// Keep the function pointers in thread-local storage, so each thread
+    /// loads its own with `gl3wInit`
+    thread_local: bool,
+
+    /// Make the function pointers `static` when `GL3W_STATIC` is defined
+    static_define: bool,
+
+    /// Generate `gl3wVersion`, describing the context `gl3wInit` loaded from
+    runtime_info: bool,
+
+    /// Also generate `gl3wInit2`, which loads through a caller-supplied
+    /// resolver
+    init2: bool,
+
+    /// Also generate `gl3wInitGroups`, which only loads the groups of
+    /// functions the caller asks for
+    init_groups: bool,
+
+    /// Return `enum gl3w_error` from `gl3wInit` instead of a bare `int`
+    error_enum: bool,
+
+    /// Include the system OpenGL headers from where each platform keeps them
+    cross_platform_includes: bool,
+
+    /// Inline the parsed headers into a single gl3w.h instead of including
+    /// them
+    self_contained: bool,
+
+    /// What to include in place of `<KHR/khrplatform.h>`, with its quotes
+    /// or brackets
+    khrplatform_include: Option<String>,
+
+    /// What gl3w.c includes gl3w.h as, if not `<GL/gl3w.h>`
+    header_include: Option<String>,
+
+    /// Move the function pointers out of gl3w.h into gl3w_internal.h
+    split_headers: bool,
+
+    /// Move the includes and typedefs out of gl3w.h into gl3w_pch.h, which
+    /// rarely changes and can be precompiled
+    pch_header: bool,
+
+    /// Generate a single header with everything `static`, so no separate
+    /// implementation is needed
+    header_only: bool,
+
+    /// Which OpenGL headers the generated header includes and we parse
+    profile: Profile,
+
+    /// `#define`s written at the top of the generated header, in order
+    defines: Vec<(String, Option<String>)>
+}
+
+impl Default for Gl3wExec {
+    fn default() -> Gl3wExec {
+        Gl3wExec {
+            command: Command::Generate,
+            url_glcorearb: URL_GLCOREARB.to_string(),
+            path_glcorearb: PathBuf::from("include/GL/glcorearb.h"),
+            url_glext: URL_GLEXT.to_string(),
+            path_glext: PathBuf::from("include/GL/glext.h"),
+            path_gl3w: Gl3wPath::Separate(
+                PathBuf::from("src/gl3w.h"),
+                PathBuf::from("src/gl3w.c")
+            ),
+            fetch: Fetch::default(),
+            client: Arc::new(Http::new(Vec::new(), false, false)),
+            verify_compile: false,
+            outputs: Vec::new(),
+            no_timestamp: false,
+            reproducible: false,
+            source_date_epoch: None,
+            stats: None,
+            depfile: None,
+            list_filtered: None,
+            cmake_package: None,
+            stdout: None,
+            bench: false,
+            if_changed: false,
+            resume: false,
+            pragma_once: false,
+            changelog: None,
+            annotate_versions: false,
+            annotate_source: false,
+            group_by_version: false,
+            fail_on_warning: false,
+            prune_enums: false,
+            inputs: Vec::new(),
+            extra_headers: Vec::new(),
+            threads: num_cpus::get(),
+            name_map: HashMap::new(),
+            proc_regex: None,
+            matches: Vec::new(),
+            exclude_matches: Vec::new(),
+            used: None,
+            extensions: None,
+            since: None,
+            vendors: Vec::new(),
+            no_deprecated: false,
+            sort_by: SortKey::Id,
+            sort_case_insensitive: false,
+            align: None,
+            crlf: false,
+            minify: false,
+            prefix: "gl3w".to_string(),
+            assert_loaded: false,
+            trace: false,
+            export_macro: None,
+            manifest_format: ManifestFormat::Text,
+            no_extern_c: false,
+            observer: None,
+            load_bitmap: false,
+            selftest: false,
+            lazy_extensions: false,
+            index_enum: false,
+            proc_names: false,
+            compat_prefixes: Vec::new(),
+            append_extensions: Vec::new(),
+            patch: None,
+            existing_header: None,
+            template_dump_dir: None,
+            calling_convention: None,
+            verbose: false,
+            quiet: false,
+            warnings: Arc::new(AtomicUsize::new(0)),
+            allow_empty: false,
+            expect_count: None,
+            loader: Loader::Native,
+            libgl_name: None,
+            require_context: false,
+            header_only: false,
+            split_headers: false,
+            pch_header: false,
+            self_contained: false,
+            khrplatform_include: None,
+            header_include: None,
+            init2: false,
+            init_groups: false,
+            error_enum: false,
+            cross_platform_includes: false,
+            runtime_info: false,
+            idempotent_init: false,
+            refcount_init: false,
+            lang: compile::Lang::C,
+            thread_local: false,
+            static_define: false,
+            profile: Profile::Core,
+            defines: Vec::new()
+        }
+    }
+}
+
+impl Gl3wExec {
+    /// Tell `observer` about the progress of each run, replacing any
+    /// `--progress` output.
+    pub fn set_observer(&mut self, observer: Arc<Gl3wObserver>) {
+        self.observer = Some(observer);
+    }
+
+    pub fn get_glcorearb_h(&self) -> io::Result<String> {
+        let mut glcorearb_h = String::new();
+
+        if !self.inputs.is_empty() {
+            // Concatenate all inputs, duplicates are removed in `gen_procs`
+            for input in &self.inputs {
+                glcorearb_h.push_str(&read_header(input)?);
+                glcorearb_h.push('\n');
+            }
+        }
+        else if self.profile == Profile::Compatibility {
+            // glext.h declares everything past OpenGL 1.1 with the deprecated
+            // functions, gl.h provides the rest directly
+            glcorearb_h = fetch_cached(&self.client, &self.url_glext, &self.path_glext, self.fetch, &self.warnings, self.observer())?;
+        }
+        else {
+            glcorearb_h = fetch_cached(&self.client, &self.url_glcorearb, &self.path_glcorearb, self.fetch, &self.warnings, self.observer())?;
+        }
+
+        if let Some(ref path) = self.patch {
+            let mut patch = String::new();
+            File::open(path).and_then(|mut f| f.read_to_string(&mut patch)).map_err(|e| {
+                io::Error::new(e.kind(), format!("failed to read {}: {}", path.display(), e))
+            })?;
+            glcorearb_h = diff::apply(&glcorearb_h, &patch).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("failed to apply {}: {}", path.display(), e))
+            })?;
+        }
+
+        for extra in self.fetch_extra_headers()? {
+            glcorearb_h.push('\n');
+            glcorearb_h.push_str(&extra);
+        }
+
+        // Some mirrors serve CRLF line endings, which would otherwise end
+        // up in prototypes spanning lines and in --self-contained output
+        if glcorearb_h.contains('\r') {
+            glcorearb_h = glcorearb_h.replace("\r\n", "\n");
+        }
+
+        Ok(glcorearb_h)
+    }
+
+    /// Print what the server reports for each header url which would be
+    /// downloaded, failing if any cannot be fetched. The cache is not used.
+    ///
+    /// Unless `report` is set only the status is printed, and anything but
+    /// `200 OK` is a failure.
+    pub fn check_urls(&self, report: bool) -> io::Result<()> {
+        let mut urls = vec![if self.profile == Profile::Compatibility { &self.url_glext } else { &self.url_glcorearb }];
+        urls.extend(self.extra_headers.iter());
+
+        for url in urls {
+            if report {
+                probe_url(&self.client, url)?;
+                continue;
+            }
+
+            let resp = head(&self.client, url)?;
+            if resp.status != hyper::status::StatusCode::Ok {
+                return Err(io::Error::new(io::ErrorKind::Other, format!("failed to fetch {}: {}", url, resp.status)));
+            }
+            if !self.quiet {
+                println!("{}: {}", url, resp.status);
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetch every `extra_headers` url, running at most `threads` downloads
+    /// at once. Each is cached next to glcorearb.h under the last component
+    /// of its url, and the contents are returned in the order given.
+    fn fetch_extra_headers(&self) -> io::Result<Vec<String>> {
+        let jobs = self.extra_headers.iter().cloned().zip(self.extra_header_paths()).collect();
+
+        let fetch = self.fetch;
+        let client = self.client.clone();
+        let warnings = self.warnings.clone();
+        let observer = self.observer.clone();
+        parallel_map(self.threads, jobs, move |(url, path)| {
+            fetch_cached(&client, &url, &path, fetch, &warnings, observer.as_ref().map(|o| &**o))
+        }).into_iter().collect()
+    }
+
+    /// Where each of `extra_headers` is cached. For GLES that is under the
+    /// include directory it is included from instead, e.g.
+    /// `include/GLES2/gl2ext.h`.
+    fn extra_header_paths(&self) -> Vec<PathBuf> {
+        self.extra_headers.iter().map(|url| {
+            let (dir, name) = extra_header_include(url);
+            let path = match self.path_glcorearb.parent().and_then(|p| p.parent()) {
+                Some(include) if self.profile == Profile::Gles3 => include.join(dir).join(name),
+                _ => self.path_glcorearb.with_file_name(name)
+            };
+            let mut path = path.into_os_string();
+            if is_gzip(&self.path_glcorearb) {
+                path.push(".gz");
+            }
+            PathBuf::from(path)
+        }).collect()
+    }
+
+    /// Every file the generated output is read from: the local inputs or
+    /// the cached header, the patch and the cached extra headers.
+    fn input_paths(&self) -> Vec<PathBuf> {
+        let mut paths = if !self.inputs.is_empty() {
+            self.inputs.clone()
+        }
+        else if self.profile == Profile::Compatibility {
+            vec![self.path_glext.clone()]
+        }
+        else {
+            vec![self.path_glcorearb.clone()]
+        };
+        paths.extend(self.patch.iter().cloned());
+        paths.extend(self.extra_header_paths());
+
+        let mut seen = HashSet::new();
+        paths.retain(|path| seen.insert(path.clone()));
+        paths
+    }
+
+    /// The observer of this run, if any.
+    fn observer(&self) -> Option<&Gl3wObserver> {
+        self.observer.as_ref().map(|o| &**o)
+    }
+
+    /// This is a associated function now for consistency and potential
+    /// future changes.
+    ///
+    /// Procs are ordered by function name. The sort is stable, so entries
+    /// with the same name keep the order they appear in the header and the
+    /// output is identical across runs on the same input.
+    ///
+    /// Functions declared more than once (as when several inputs are
+    /// combined) are only kept once. The glcorearb.h declaration wins if
+    /// there is one, otherwise the first.
+    ///
+    /// Functions which only have a `PFN*PROC` typedef and no `GLAPI`
+    /// prototype are included too. The typedef name is all uppercase, so the
+    /// real name is recovered from another mention of it in the header.
+    ///
+    /// Prototypes may be exported with `WINGDIAPI` (the Windows gl.h) or
+    /// `GL_APICALL ... GL_APIENTRY` (the Khronos ES headers) instead.
+    pub fn gen_procs(&self, glcorearb_h: &str) -> Vec<Proc> {
+        self.gen_procs_filtered(glcorearb_h).0
+    }
+
+    /// `gen_procs`, along with the names of the functions the filters
+    /// (`--match`, `--exclude-match`, `--from-nm`, `--extensions-from`,
+    /// `--no-deprecated`, `--since` and `--vendor`) left out, ordered by name.
+    fn gen_procs_filtered(&self, glcorearb_h: &str) -> (Vec<Proc>, Vec<String>) {
+        // Anything may come before the export macro, e.g. a visibility
+        // attribute, and the calling convention may be Mesa's `GLAPIENTRY`
+        let re = regex::Regex::new(
+            r"\b(?:GLAPI|WINGDIAPI|GL_APICALL)[ \t]+([^;\n]*?)[ \t]*\b(?:GL_?)?APIENTRY[ \t]+(\w+)[ \t]*\(([^)]*)\)"
+        ).unwrap();
+        let re_attr = regex::Regex::new(r"(?:__attribute__[ \t]*\(\((?:[^()]|\([^()]*\))*\)\)|__declspec[ \t]*\([^)]*\))[ \t]*").unwrap();
+        let ret = |ret: &str| re_attr.replace_all(ret, "").trim().to_string();
+        let blocks = gl_blocks(glcorearb_h);
+        let sources = gl_sources(glcorearb_h);
+        let locate = |p: Proc, offset: usize| Proc {
+            block: block_at(&blocks, offset),
+            source: block_at(&sources, offset),
+            offset,
+            ..p
+        };
+
+        let mut procs = match self.proc_regex {
+            // Only the name is known from a user pattern, which may not
+            // capture anything or capture something other than a function
+            Some(ref re) => re.captures_iter(glcorearb_h).filter_map(|e| {
+                let offset = e.pos(0).unwrap().0;
+                match e.at(1) {
+                    Some(id) if id.starts_with("gl") && id.len() > 2 => Some(locate(Proc::new(id), offset)),
+                    Some(id) => {
+                        warn(self.quiet, &self.warnings, &format!("--proc-regex captured `{}`, which is not a GL function, skipping", id));
+                        None
+                    }
+                    None => {
+                        warn(self.quiet, &self.warnings, &format!("--proc-regex matched `{}` without capturing a name, skipping",
+                                                  e.at(0).unwrap()));
+                        None
+                    }
+                }
+            }).collect::<Vec<_>>(),
+
+            None => re.captures_iter(glcorearb_h)
+                      .map(|e| locate(Proc::with_proto(&e[2], &ret(&e[1]), &e[3]), e.pos(0).unwrap().0))
+                      .collect::<Vec<_>>()
+        };
+
+        let re_pfn = regex::Regex::new(r"\btypedef\s[^;]*\(\s*APIENTRYP\s+PFN(GL\w+)PROC\s*\)").unwrap();
+        let declared = procs.iter().map(|p| p.id.to_uppercase()).collect::<HashSet<_>>();
+        let mut names = HashMap::new();
+
+        for e in re_pfn.captures_iter(glcorearb_h) {
+            if declared.contains(&e[1]) {
+                continue;
+            }
+
+            if names.is_empty() {
+                names = gl_identifiers(glcorearb_h);
+            }
+
+            match names.get(&e[1]) {
+                Some(id) => procs.push(locate(Proc::new(id), e.pos(0).unwrap().0)),
+                None => warn(self.quiet, &self.warnings, &format!("cannot recover the function name for PFN{}PROC, skipping", &e[1]))
+            }
+        }
+
+        procs.sort_by(|a, b| self.cmp_names(&a.id, &b.id).then(b.is_core().cmp(&a.is_core())));
+        procs.dedup_by(|dup, kept| {
+            if dup.id != kept.id {
+                return false;
+            }
+
+            if self.verbose && dup.source != kept.source {
+                eprintln!("{}: using the declaration from {}, ignoring {}", kept.id,
+                          kept.source.as_ref().map_or("an unknown header", |s| s.as_str()),
+                          dup.source.as_ref().map_or("an unknown header", |s| s.as_str()));
+            }
+            true
+        });
+
+        for p in &mut procs {
+            if self.prefix != "gl3w" {
+                p.var = format!("{}{}", self.prefix, &p.id[2..]);
+            }
+            if let Some(var) = self.name_map.get(&p.id) {
+                p.var = var.clone();
+            }
+        }
+
+        for id in self.name_map.keys() {
+            if !procs.iter().any(|p| p.id == *id) {
+                warn(self.quiet, &self.warnings, &format!("`{}` in the name map is not a known function", id));
+            }
+        }
+
+        let unfiltered = procs.iter().map(|p| p.id.clone()).collect::<Vec<_>>();
+
+        // gl3wInit queries the version with glGetIntegerv, so it is never
+        // filtered out
+        procs.retain(|p| {
+            p.id == "glGetIntegerv" ||
+            (self.matches.is_empty() || self.matches.iter().any(|re| re.is_match(&p.id))) &&
+            !self.exclude_matches.iter().any(|re| re.is_match(&p.id))
+        });
+
+        if let Some(ref used) = self.used {
+            procs.retain(|p| p.id == "glGetIntegerv" || used.contains(&p.id));
+        }
+
+        if let Some(ref extensions) = self.extensions {
+            procs.retain(|p| extension_of(p).map_or(true, |ext| extensions.contains(ext)));
+        }
+
+        if self.no_deprecated {
+            let deprecated = include_str!("deprecated.txt").lines()
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .collect::<HashSet<_>>();
+            procs.retain(|p| !deprecated.contains(p.id.as_str()));
+        }
+
+        if let Some(since) = self.since {
+            procs.retain(|p| {
+                p.id == "glGetIntegerv" ||
+                p.block.as_ref().and_then(|b| block_version(b)).map_or(false, |v| v >= since)
+            });
+        }
+
+        if !self.vendors.is_empty() {
+            procs.retain(|p| match p.block.as_ref().and_then(|b| block_vendor(b)) {
+                Some(vendor) => self.vendors.iter().any(|v| v == vendor),
+                None => true
+            });
+        }
+
+        let kept = procs.iter().map(|p| p.id.as_str()).collect::<HashSet<_>>();
+        let filtered = unfiltered.iter().filter(|id| !kept.contains(id.as_str())).cloned().collect::<Vec<_>>();
+
+        // Some gl.h versions already declare later functions (Mesa goes up to
+        // OpenGL 1.3) which hides the matching glext.h typedefs, so we
+        // declare our own pointer types instead. These are also needed to
+        // use another calling convention.
+        if self.own_typedefs() {
+            for p in procs.iter_mut().filter(|p| p.proto.is_some()) {
+                p.pfn = format!("GL3W_{}", p.pfn);
+            }
+        }
+
+        // Already sorted by id for removing duplicates
+        match self.sort_by {
+            SortKey::Id => {}
+            SortKey::Pfn => procs.sort_by(|a, b| self.cmp_names(&a.pfn, &b.pfn)),
+            SortKey::Var => procs.sort_by(|a, b| self.cmp_names(&a.var, &b.var)),
+            SortKey::Source => procs.sort_by_key(|p| p.offset)
+        }
+
+        (procs, filtered)
+    }
+
+    /// Compare names to sort by, ignoring case with
+    /// `--sort-case-insensitive`. Names only differing in case are still
+    /// kept apart so duplicates end up next to each other.
+    fn cmp_names(&self, a: &str, b: &str) -> cmp::Ordering {
+        if self.sort_case_insensitive {
+            a.to_lowercase().cmp(&b.to_lowercase()).then_with(|| a.cmp(b))
+        }
+        else {
+            a.cmp(b)
+        }
+    }
+
+    /// Extension names, from the `#ifndef GL_<EXT>` guard around each
+    /// extension block. Names are sorted and only listed once.
+    pub fn gen_extensions(&self, glcorearb_h: &str) -> Vec<String> {
+        let mut extensions = gl_blocks(glcorearb_h).into_iter()
+                               .filter_map(|(_, block)| block)
+                               .filter(|block| !block.starts_with("GL_VERSION_"))
+                               .collect::<Vec<_>>();
+
+        extensions.sort();
+        extensions.dedup();
+        extensions
+    }
+
+    /// The highest OpenGL version with a `#define GL_VERSION_x_y 1` presence
+    /// marker in the header. Markers with numbers too large for a `u32`
+    /// are ignored.
+    pub fn detected_version(&self, glcorearb_h: &str) -> Option<(u32, u32)> {
+        let re = regex::Regex::new(r"(?m)^#define\s+GL_VERSION_(\d+)_(\d+)\s+1\s*$").unwrap();
+        re.captures_iter(glcorearb_h)
+          .filter_map(|e| match (e[1].parse().ok(), e[2].parse().ok()) {
+              (Some(major), Some(minor)) => Some((major, minor)),
+              _ => None
+          })
+          .max()
+    }
+
+    /// Extract all `#define GL_* <value>` constants in header order.
+    ///
+    /// Version and extension presence markers (`GL_VERSION_1_0`,
+    /// `GL_ARB_imaging`, ...) are not constants and are skipped.
+    ///
+    /// With `prune_enums` only constants from the blocks declaring one of
+    /// `procs` are kept. Prototypes take a plain `GLenum`, so the block is
+    /// the closest link between a function and the constants it uses.
+    fn gen_enums(&self, glcorearb_h: &str, procs: &[Proc]) -> Vec<Enum> {
+        let re = regex::Regex::new(
+            r"(?m)^#define\s+(GL_[A-Z0-9_]+)\s+(0x[0-9A-Fa-f]+[uUlL]*|[0-9]+[uUlL]*)\s*$"
+        ).unwrap();
+        let blocks = gl_blocks(glcorearb_h);
+        let markers = blocks.iter()
+                            .filter_map(|&(_, ref block)| block.as_ref().map(|b| b.as_str()))
+                            .collect::<HashSet<_>>();
+        let used = procs.iter().filter_map(|p| p.block.as_ref()).collect::<HashSet<_>>();
+
+        let mut seen = HashSet::new();
+        let mut enums = Vec::new();
+        for e in re.captures_iter(glcorearb_h) {
+            if self.prune_enums {
+                let block = block_at(&blocks, e.pos(0).unwrap().0);
+                if !block.as_ref().map_or(false, |b| used.contains(b)) {
+                    continue;
+                }
+            }
+            if markers.contains(&e[1]) || !seen.insert(e[1].to_string()) {
+                continue;
+            }
+            enums.push(Enum(e[1].to_string(), e[2].to_string()));
+        }
+
+        enums
+    }
+
+    /// Find the type declarations required by the prototypes of `procs`.
+    ///
+    /// This follows dependencies between typedefs (e.g. `GLDEBUGPROC` needs
+    /// `GLenum`) and returns them in header order, so each appears before
+    /// anything using it.
+    fn gen_typedefs(&self, glcorearb_h: &str, procs: &[Proc]) -> Vec<Typedef> {
+        let re_decl = regex::Regex::new(r"(?m)^(typedef\s[^;]*|struct\s+\w+\s*);").unwrap();
+        let re_fnptr = regex::Regex::new(r"\(\s*(?:APIENTRY\s*\*|APIENTRYP)\s*(\w+)\s*\)").unwrap();
+        let re_name = regex::Regex::new(r"(\w+)\s*;$").unwrap();
+        let re_ident = regex::Regex::new(r"[A-Za-z_]\w*").unwrap();
+
+        let mut typedefs = Vec::new();
+        for e in re_decl.captures_iter(glcorearb_h) {
+            let decl = &e[0];
+            let name = match re_fnptr.captures(decl).or_else(|| re_name.captures(decl)) {
+                Some(name) => name[1].to_string(),
+                None => continue
+            };
+
+            if !name.starts_with("PFN") {
+                typedefs.push(Typedef(name, decl.to_string()));
+            }
+        }
+
+        let mut pending = procs.iter()
+                               .filter_map(|p| p.proto.as_ref())
+                               .map(|&(ref ret, ref params)| format!("{} {}", ret, params))
+                               .collect::<Vec<_>>();
+
+        // Walked with an explicit stack, and looked up by name, so huge
+        // headers stay linear
+        let mut by_name = HashMap::new();
+        for t in &typedefs {
+            by_name.entry(t.0.as_str()).or_insert_with(|| t.1.as_str());
+        }
+        let mut needed = HashSet::new();
+        while let Some(text) = pending.pop() {
+            for ident in re_ident.find_iter(&text) {
+                let ident = &text[ident.0..ident.1];
+                if let Some(decl) = by_name.get(ident) {
+                    if needed.insert(ident.to_string()) {
+                        pending.push(decl.to_string());
+                    }
+                }
+            }
+        }
+
+        typedefs.into_iter().filter(|t| needed.contains(&t.0)).collect()
+    }
+
+    /// Write an extra output file, returning its path.
+    fn gen_output(&self, target: &OutputTarget, glcorearb_h: &str, procs: &[Proc]) -> io::Result<PathBuf> {
+        let path = target.path();
+
+        create_parent_dir(path)?;
+        let mut f = File::create(path)?;
+
+        match *target {
+            OutputTarget::Enums(_) => {
+                let enums = self.gen_enums(glcorearb_h, procs);
+                if path.extension().map_or(false, |ext| ext == "json") {
+                    gen_enums_json(&mut f, &enums)?;
+                }
+                else {
+                    gen_enums_h(&mut f, &enums)?;
+                }
+            }
+
+            OutputTarget::Typedefs(_) => {
+                gen_typedefs_h(&mut f, self, &self.gen_typedefs(glcorearb_h, procs))?;
+            }
+
+            OutputTarget::SmokeTest(_) => gen_smoke_test(&mut f, self)?,
+
+            OutputTarget::CompileCommands(_) => self.gen_compile_commands(&mut f)?,
+
+            OutputTarget::Manifest(_) => gen_manifest(&mut f, self, procs)?,
+
+            OutputTarget::Procs(_) => gen_procs_json(&mut f, procs)?,
+
+            OutputTarget::ProcsSchema(_) => gen_procs_schema(&mut f, self)?
+        }
+
+        Ok(path.clone())
+    }
+
+    /// Whether we declare the function pointer types instead of using the
+    /// header's.
+    fn own_typedefs(&self) -> bool {
+        self.profile == Profile::Compatibility || self.calling_convention.is_some()
+    }
+
+    /// Remove the `extern "C"` block from `template` with `--no-extern-c`.
+    fn extern_c(&self, template: &str) -> String {
+        if self.no_extern_c {
+            template.replacen(EXTERN_C_BEGIN, "", 1).replacen(EXTERN_C_END, "", 1)
+        }
+        else {
+            template.to_string()
+        }
+    }
+
+    /// The OpenGL header includes emitted at the top of gl3w.h.
+    fn profile_template(&self) -> &'static str {
+        if self.cross_platform_includes {
+            self.profile.cross_template()
+        }
+        else {
+            self.profile.template()
+        }
+    }
+
+    /// `profile_template` along with an include of each of the GLES
+    /// extension headers among the `--extra-header`s, whose typedefs the
+    /// generated pointers use.
+    fn profile_includes(&self) -> String {
+        let template = self.profile_template();
+        if self.profile != Profile::Gles3 || self.extra_headers.is_empty() {
+            return template.to_string();
+        }
+
+        let includes = self.extra_headers.iter().map(|url| {
+            let (dir, name) = extra_header_include(url);
+            format!("#include <{}/{}>\n", dir, name)
+        }).collect::<String>();
+        template.replacen("\n\n", &format!("\n{}\n", includes), 1)
+    }
+
+    /// Return `enum gl3w_error` from the gl3w functions and their helpers in
+    /// `template` with `--error-enum`, declaring the enum before the api.
+    fn error_enum(&self, template: &str) -> String {
+        if !self.error_enum {
+            return template.to_string();
+        }
+
+        let mut template = template.replace(
+            "/* gl3w api */\n", &format!("{}/* gl3w api */\n", include_str!("template/gl3w.error.h"))
+        );
+        for f in STATUS_FUNCTIONS {
+            template = template.replace(&format!("int {}", f), &format!("enum gl3w_error {}", f));
+        }
+        for &(from, to) in STATUS_RETURNS {
+            template = template.replace(from, to);
+        }
+        template
+    }
+
+    /// Prefix each gl3w api declaration in `api` with the `--export-macro`.
+    fn exported(&self, api: &str) -> String {
+        let export = match self.export_macro {
+            Some(ref export) => export,
+            None => return api.to_string()
+        };
+
+        api.split('\n').map(|line| {
+            if line.starts_with("extern ") && !line.starts_with("extern \"C\"") {
+                format!("extern {} {}", export, &line["extern ".len()..])
+            }
+            else if line.ends_with(");") && !line.starts_with("typedef") {
+                format!("{} {}", export, line)
+            }
+            else {
+                line.to_string()
+            }
+        }).collect::<Vec<_>>().join("\n")
+    }
+
+    /// Whether `gl3wVersion` is generated, which needs `glGetString` to
+    /// identify the context.
+    fn has_runtime_info(&self, procs: &[Proc]) -> bool {
+        self.runtime_info && procs.iter().any(|p| p.id == "glGetString")
+    }
+
+    /// The path of the smoke test program, if one is generated.
+    fn path_smoke_test(&self) -> Option<&PathBuf> {
+        self.outputs.iter().filter_map(|target| match *target {
+            OutputTarget::SmokeTest(ref path) => Some(path),
+            _ => None
+        }).next()
+    }
+
+    /// The path of the manifest, if one is generated.
+    fn path_manifest(&self) -> Option<&PathBuf> {
+        self.outputs.iter().filter_map(|target| match *target {
+            OutputTarget::Manifest(ref path) => Some(path),
+            _ => None
+        }).next()
+    }
+
+    /// Append a dated section to the changelog listing the functions added
+    /// and removed since `previous`, the functions of the last manifest.
+    ///
+    /// Nothing is written if nothing changed.
+    fn gen_changelog(&self, path: &Path, previous: &[String], procs: &[Proc]) -> io::Result<bool> {
+        let previous = previous.iter().map(|id| id.as_str()).collect::<HashSet<_>>();
+        let current = procs.iter().map(|p| p.id.as_str()).collect::<HashSet<_>>();
+
+        let mut added = current.difference(&previous).cloned().collect::<Vec<_>>();
+        let mut removed = previous.difference(&current).cloned().collect::<Vec<_>>();
+        if added.is_empty() && removed.is_empty() {
+            return Ok(false);
+        }
+        added.sort();
+        removed.sort();
+
+        create_parent_dir(path)?;
+        let mut f = fs::OpenOptions::new().create(true).append(true).open(path)?;
+        match self.date() {
+            Some(date) => {
+                writeln!(f, "## {}\n", date.strftime("%Y-%m-%d").unwrap())?;
+                writeln!(f, "Generated from {}\n", self.source())?;
+            }
+            None => writeln!(f, "## {}\n", self.source())?
+        }
+        for &(title, ids) in &[("Added", &added), ("Removed", &removed)] {
+            if !ids.is_empty() {
+                writeln!(f, "### {}\n", title)?;
+                for id in ids {
+                    writeln!(f, "- `{}`", id)?;
+                }
+                writeln!(f, "")?;
+            }
+        }
+        Ok(true)
+    }
+
+    /// Generate a `compile_commands.json` for the generated source.
+    ///
+    /// The generated source includes `<GL/glcorearb.h>`, so the directory
+    /// above the cached glcorearb.h is on the include path.
+    fn gen_compile_commands<T: Write>(&self, out: &mut T) -> io::Result<()> {
+        let cwd = env::current_dir()?;
+        let include = cwd.join(self.path_glcorearb.parent().and_then(Path::parent).unwrap_or(Path::new("")));
+
+        let (file, flags) = match self.path_gl3w {
+            Gl3wPath::Single(ref path) if !self.header_only => (path, vec!["-DGL3W_IMPLEMENTATION", "-x", "c"]),
+            Gl3wPath::Single(ref path) => (path, vec!["-x", "c"]),
+            Gl3wPath::Separate(_, ref path_c) => (path_c, vec![])
+        };
+        let file = cwd.join(file);
+
+        let mut arguments = vec!["cc".to_string(), format!("-I{}", include.display())];
+        arguments.extend(flags.iter().map(|f| f.to_string()));
+        arguments.push("-c".to_string());
+        arguments.push(file.display().to_string());
+
+        gen_compile_commands(out, &cwd, &file, &arguments)
+    }
+
+    /// Copy the gl3w `files` just generated into a CMake package in `dir`,
+    /// returning the paths written.
+    ///
+    /// Headers go in `include/GL` along with the parsed header they
+    /// include, and the source in `src`. A single file gets a source of its
+    /// own defining `GL3W_IMPLEMENTATION`.
+    fn gen_cmake_package(&self, dir: &Path, files: &[PathBuf], glcorearb_h: &str) -> io::Result<Vec<PathBuf>> {
+        let include = dir.join("include").join("GL");
+        fs::create_dir_all(&include)?;
+        fs::create_dir_all(dir.join("src"))?;
+
+        let mut written = Vec::new();
+        let mut sources = Vec::new();
+        for file in files {
+            let name = file.file_name().unwrap_or_default();
+            let to = match self.path_gl3w {
+                Gl3wPath::Separate(_, ref path_c) if file == path_c => {
+                    sources.push(name.to_string_lossy().into_owned());
+                    dir.join("src").join(name)
+                }
+                _ => include.join(name)
+            };
+            fs::copy(file, &to)?;
+            written.push(to);
+        }
+
+        if let Gl3wPath::Single(_) = self.path_gl3w {
+            if !self.header_only {
+                let path = dir.join("src").join("gl3w.c");
+                let source = format!("#define GL3W_IMPLEMENTATION\n#include <GL/{}>\n", self.header_name());
+                File::create(&path)?.write_all(source.as_bytes())?;
+                sources.push("gl3w.c".to_string());
+                written.push(path);
+            }
+        }
+
+        if !self.self_contained {
+            let path = dir.join("include").join(self.profile.include_dir()).join(self.profile.header());
+            create_parent_dir(&path)?;
+            File::create(&path)?.write_all(glcorearb_h.as_bytes())?;
+            written.push(path);
+        }
+
+        let path = dir.join("gl3wConfig.cmake");
+        gen_cmake_config(&mut File::create(&path)?, self, &sources)?;
+        written.push(path);
+        Ok(written)
+    }
+
+    /// Generate the required files from the specified proc.
+    ///
+    /// Return the paths written if successfull else error. An empty `procs`
+    /// is an error unless `allow_empty` is set, as it almost certainly means
+    /// the header could not be parsed.
+    pub fn gen(&self, glcorearb_h: &str, procs: &[Proc], version: Option<(u32, u32)>) -> io::Result<Vec<PathBuf>> {
+        if procs.is_empty() && !self.allow_empty {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+                "no OpenGL functions found in {}; the header format may have changed",
+                self.source()
+            )));
+        }
+
+        self.check_writable()?;
+
+        match self.path_gl3w {
+            Gl3wPath::Single(_) if self.stdout.is_some() => {
+                io::stdout().write_all(self.generate_single(glcorearb_h, procs, version).as_bytes())?;
+                Ok(Vec::new())
+            }
+
+            Gl3wPath::Single(ref path) => {
+                create_parent_dir(path)?;
+                File::create(path)?.write_all(self.generate_single(glcorearb_h, procs, version).as_bytes())?;
+                Ok(vec![path.clone()])
+            }
+
+            Gl3wPath::Separate(ref path_h, ref path_c)  => {
+                let mut files = Vec::new();
+                match self.stdout {
+                    Some(Artifact::Header) => {
+                        io::stdout().write_all(self.generate_header(glcorearb_h, procs, version).as_bytes())?;
+                        files.push((path_c.clone(), self.generate_source(procs)));
+                    }
+                    Some(Artifact::Source) => {
+                        io::stdout().write_all(self.generate_source(procs).as_bytes())?;
+                        files.push((path_h.clone(), self.generate_header(glcorearb_h, procs, version)));
+                    }
+                    None => {
+                        files.push((path_h.clone(), self.generate_header(glcorearb_h, procs, version)));
+                        files.push((path_c.clone(), self.generate_source(procs)));
+                    }
+                }
+                for &(ref path, _) in &files {
+                    create_parent_dir(path)?;
+                }
+
+                if let Some(path) = self.path_internal_header() {
+                    files.insert(1, (path, self.generate_internal_header(procs)));
+                }
+                if let Some(path) = self.path_pch_header() {
+                    files.insert(1, (path, self.generate_pch_header(procs)));
+                }
+                self.write_resumable(&files)
+            }
+        }
+    }
+
+    /// Write each of `files` with its contents. With `--resume`, every file
+    /// written is recorded with its hash until they all are, and those a
+    /// failed run recorded which are still intact are not written again.
+    ///
+    /// The precompiled header is not rewritten if it is unchanged, as that
+    /// would still invalidate it.
+    fn write_resumable(&self, files: &[(PathBuf, String)]) -> io::Result<Vec<PathBuf>> {
+        let path_pch = self.path_pch_header();
+        let path_resume = self.path_resume();
+        let mut done = HashMap::new();
+        if self.resume {
+            let mut resume = String::new();
+            match File::open(&path_resume) {
+                Ok(mut f) => { f.read_to_string(&mut resume)?; }
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e)
+            }
+            for line in resume.lines() {
+                let mut parts = line.splitn(2, "  ");
+                if let (Some(digest), Some(path)) = (parts.next(), parts.next()) {
+                    done.insert(PathBuf::from(path), digest.to_string());
+                }
+            }
+        }
+
+        let mut record = None;
+        for &(ref path, ref contents) in files {
+            let digest = sha256::hex_digest(contents.as_bytes());
+            let resumed = done.get(path) == Some(&digest);
+            let intact = (resumed || Some(path) == path_pch.as_ref()) && {
+                let mut existing = Vec::new();
+                File::open(path).and_then(|mut f| f.read_to_end(&mut existing)).is_ok() &&
+                sha256::hex_digest(&existing) == digest
+            };
+            if intact && resumed {
+                if self.verbose {
+                    eprintln!("{} was written by the failed run, skipping", path.display());
+                }
+                continue;
+            }
+
+            if !intact {
+                File::create(path)?.write_all(contents.as_bytes())?;
+            }
+            if self.resume {
+                if record.is_none() {
+                    record = Some(fs::OpenOptions::new().create(true).append(true).open(&path_resume)?);
+                }
+                if let Some(ref mut f) = record {
+                    writeln!(f, "{}  {}", digest, path.display())?;
+                }
+            }
+        }
+
+        if self.resume && path_resume.exists() {
+            fs::remove_file(&path_resume)?;
+        }
+        Ok(files.iter().map(|&(ref path, _)| path.clone()).collect())
+    }
+
+    /// Every file generating writes, in the order it is written. The
+    /// `--stdout` file and our own state files are left out.
+    fn output_paths(&self) -> Vec<PathBuf> {
+        let gl3w = match self.path_gl3w {
+            Gl3wPath::Single(_) if self.stdout.is_some() => vec![],
+            Gl3wPath::Single(ref path) => vec![path.clone()],
+            Gl3wPath::Separate(ref path_h, ref path_c) => {
+                let mut paths = Vec::new();
+                if self.stdout != Some(Artifact::Header) {
+                    paths.push(path_h.clone());
+                }
+                paths.extend(self.path_pch_header());
+                paths.extend(self.path_internal_header());
+                if self.stdout != Some(Artifact::Source) {
+                    paths.push(path_c.clone());
+                }
+                paths
+            }
+        };
+
+        let mut paths = gl3w.clone();
+        if let Some(ref dir) = self.cmake_package {
+            // As laid out by gen_cmake_package
+            let include = dir.join("include").join("GL");
+            for path in gl3w {
+                let name = path.file_name().unwrap_or_default().to_os_string();
+                match self.path_gl3w {
+                    Gl3wPath::Separate(_, ref path_c) if path == *path_c => paths.push(dir.join("src").join(name)),
+                    _ => paths.push(include.join(name))
+                }
+            }
+            if let Gl3wPath::Single(_) = self.path_gl3w {
+                if !self.header_only {
+                    paths.push(dir.join("src").join("gl3w.c"));
+                }
+            }
+            if !self.self_contained {
+                paths.push(dir.join("include").join(self.profile.include_dir()).join(self.profile.header()));
+            }
+            paths.push(dir.join("gl3wConfig.cmake"));
+        }
+        paths.extend(self.outputs.iter().map(|target| target.path().clone()));
+        paths.extend(self.changelog.iter().cloned());
+        paths.extend(self.list_filtered.iter().cloned());
+        paths.extend(self.stats.iter().cloned());
+        paths.extend(self.depfile.iter().cloned());
+        paths
+    }
+
+    /// Check every directory we will write to is writable, creating them if
+    /// needed, so a failure is reported before anything is written.
+    fn check_writable(&self) -> io::Result<()> {
+        let mut paths = match self.path_gl3w {
+            Gl3wPath::Single(ref path) => vec![path],
+            Gl3wPath::Separate(ref path_h, ref path_c) => vec![path_h, path_c]
+        };
+        paths.extend(self.outputs.iter().map(|target| target.path()));
+        paths.extend(self.stats.iter());
+        paths.extend(self.depfile.iter());
+        paths.extend(self.list_filtered.iter());
+        paths.extend(self.changelog.iter());
+
+        let mut checked = HashSet::new();
+        for path in paths {
+            let dir = match path.parent() {
+                Some(parent) if parent != Path::new("") => parent,
+                _ => Path::new(".")
+            };
+            if !checked.insert(dir) {
+                continue;
+            }
+
+            let probe = dir.join(".gl3w-native-probe");
+            let result = create_parent_dir(path).and_then(|_| File::create(&probe));
+            match result {
+                Ok(_) => fs::remove_file(&probe)?,
+                Err(ref e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                    return Err(io::Error::new(io::ErrorKind::PermissionDenied,
+                        format!("cannot write to {}: permission denied", dir.display())));
+                }
+                Err(e) => {
+                    return Err(io::Error::new(e.kind(), format!("cannot write to {}: {}", dir.display(), e)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Generate gl3w.h for the specified procs without touching the
+    /// filesystem.
+    ///
+    /// ```
+    /// use gl3w_native::{Gl3wExec, Proc};
+    ///
+    /// let header = Gl3wExec::default().generate_header("", &[Proc::new("glClear")], None);
+    /// assert!(header.contains("extern PFNGLCLEARPROC "));
+    /// assert!(header.contains(" gl3wClear;"));
+    /// ```
+    pub fn generate_header(&self, glcorearb_h: &str, procs: &[Proc], version: Option<(u32, u32)>) -> String {
+        self.line_endings(gen_string(|out| {
+            self.gen_metadata(out, procs)?;
+            gen_gl3w_h(out, self, glcorearb_h, procs, version)
+        }))
+    }
+
+    /// Generate gl3w_internal.h for `--split-headers` without touching the
+    /// filesystem.
+    pub fn generate_internal_header(&self, procs: &[Proc]) -> String {
+        self.line_endings(gen_string(|out| {
+            self.gen_metadata(out, procs)?;
+            gen_gl3w_internal_h(out, self, procs)
+        }))
+    }
+
+    /// Where gl3w_internal.h is written with `--split-headers`, next to
+    /// gl3w.h and named after it.
+    fn path_internal_header(&self) -> Option<PathBuf> {
+        match self.path_gl3w {
+            Gl3wPath::Separate(ref path_h, _) if self.split_headers => {
+                Some(path_h.with_file_name(self.internal_header_name()))
+            }
+            _ => None
+        }
+    }
+
+    /// The file name of the generated gl3w.h, which generated files include
+    /// as `<GL/name>`.
+    fn header_name(&self) -> String {
+        let path = match self.path_gl3w {
+            Gl3wPath::Single(ref path) | Gl3wPath::Separate(ref path, _) => path
+        };
+        path.file_name().map_or("gl3w.h".to_string(), |name| name.to_string_lossy().into_owned())
+    }
+
+    /// The file name of gl3w_internal.h for `--split-headers`.
+    fn internal_header_name(&self) -> String {
+        let header = self.header_name();
+        let stem = header.rfind('.').map_or(&header[..], |i| &header[..i]);
+        format!("{}_internal.h", stem)
+    }
+
+    /// Where gl3w_pch.h is written with `--pch-header`, next to gl3w.h.
+    fn path_pch_header(&self) -> Option<PathBuf> {
+        match self.path_gl3w {
+            Gl3wPath::Separate(ref path_h, _) if self.pch_header => {
+                Some(path_h.with_file_name(self.pch_header_name()))
+            }
+            _ => None
+        }
+    }
+
+    /// The file name of gl3w_pch.h for `--pch-header`.
+    fn pch_header_name(&self) -> String {
+        let header = self.header_name();
+        let stem = header.rfind('.').map_or(&header[..], |i| &header[..i]);
+        format!("{}_pch.h", stem)
+    }
+
+    /// Generate gl3w_pch.h for `--pch-header` without touching the
+    /// filesystem.
+    ///
+    /// There is no metadata comment, as the function count and date would
+    /// change the file when nothing it declares has.
+    pub fn generate_pch_header(&self, procs: &[Proc]) -> String {
+        self.line_endings(gen_string(|out| gen_gl3w_pch_h(out, self, procs)))
+    }
+
+    /// Generate gl3w.c for the specified procs without touching the
+    /// filesystem.
+    ///
+    /// ```
+    /// use gl3w_native::{Gl3wExec, Proc};
+    ///
+    /// let source = Gl3wExec::default().generate_source(&[Proc::new("glClear")]);
+    /// assert!(source.contains(r#"gl3wClear = (PFNGLCLEARPROC) get_proc("glClear");"#));
+    /// ```
+    pub fn generate_source(&self, procs: &[Proc]) -> String {
+        self.line_endings(gen_string(|out| {
+            self.gen_metadata(out, procs)?;
+            gen_gl3w_c(out, self, procs)
+        }))
+    }
+
+    /// Generate the single-header gl3w.h for the specified procs without
+    /// touching the filesystem.
+    ///
+    /// ```
+    /// use gl3w_native::{Gl3wExec, Proc};
+    ///
+    /// let single = Gl3wExec::default().generate_single("", &[Proc::new("glClear")], None);
+    /// assert!(single.contains("extern PFNGLCLEARPROC "));
+    /// assert!(single.contains("#if defined(GL3W_IMPLEMENTATION)"));
+    /// ```
+    pub fn generate_single(&self, glcorearb_h: &str, procs: &[Proc], version: Option<(u32, u32)>) -> String {
+        self.line_endings(gen_string(|out| {
+            self.gen_metadata(out, procs)?;
+            gen_gl3w_single(out, self, glcorearb_h, procs, version)
+        }))
+    }
+
+    /// Write gl3w.h and gl3w.c for `procs` to `header` and `source`, for
+    /// embedders generating somewhere other than files. Nothing is parsed,
+    /// so options needing the header's text (e.g. `--self-contained`) have
+    /// nothing to work with.
+    ///
+    /// The writers are `&mut Write` rather than `&mut dyn Write` as `dyn`
+    /// needs a newer compiler than the rustc 1.19 this builds with.
+    ///
+    /// ```
+    /// use gl3w_native::parse_args;
+    ///
+    /// let exec = parse_args(vec!["--prefix".to_string(), "engine".to_string()].into_iter()).unwrap();
+    /// let procs = exec.gen_procs("GLAPI void APIENTRY glClear (GLbitfield mask);\n");
+    ///
+    /// let (mut header, mut source) = (Vec::new(), Vec::new());
+    /// exec.generate_to_writers(&procs, &mut header, &mut source).unwrap();
+    ///
+    /// let source = String::from_utf8(source).unwrap();
+    /// assert!(String::from_utf8(header).unwrap().contains(" engineClear;"));
+    /// assert!(source.contains(r#"engineClear = (PFNGLCLEARPROC) get_proc("glClear");"#));
+    /// ```
+    pub fn generate_to_writers(&self, procs: &[Proc], header: &mut Write, source: &mut Write) -> io::Result<()> {
+        let version = procs.iter().filter_map(|p| p.block.as_ref().and_then(|b| block_version(b))).max();
+        header.write_all(self.generate_header("", procs, version).as_bytes())?;
+        source.write_all(self.generate_source(procs).as_bytes())
+    }
+
+    /// Write the single-header gl3w.h for `procs` to `out`, as for
+    /// `generate_to_writers`.
+    pub fn generate_to_writer(&self, procs: &[Proc], out: &mut Write) -> io::Result<()> {
+        let version = procs.iter().filter_map(|p| p.block.as_ref().and_then(|b| block_version(b))).max();
+        out.write_all(self.generate_single("", procs, version).as_bytes())
+    }
+
+    /// Generate the gl3w files into `OUT_DIR` from a Cargo build script,
+    /// printing `cargo:rerun-if-changed` for each header they are generated
+    /// from so that refreshing the cache generates them again. Only the
+    /// gl3w files move, other outputs are written where the options say.
+    ///
+    /// Returns every file written, as `--list-outputs` prints them.
+    ///
+    /// ```no_run
+    /// // build.rs
+    /// extern crate gl3w_native;
+    ///
+    /// let args = vec!["--no-timestamp".to_string()];
+    /// let mut exec = gl3w_native::parse_args(args.into_iter()).unwrap();
+    /// let written = exec.generate_in_out_dir().unwrap();
+    /// ```
+    pub fn generate_in_out_dir(&mut self) -> io::Result<Vec<PathBuf>> {
+        let out_dir = env::var_os("OUT_DIR").ok_or_else(|| io::Error::new(
+            io::ErrorKind::NotFound, "OUT_DIR is not set, it is only set for build scripts"
+        ))?;
+        self.generate_in(Path::new(&out_dir), &mut io::stdout())
+    }
+
+    /// `generate_in_out_dir` with the gl3w files in `out_dir`, writing the
+    /// instructions for Cargo to `cargo`.
+    fn generate_in(&mut self, out_dir: &Path, cargo: &mut Write) -> io::Result<Vec<PathBuf>> {
+        let path_gl3w = match self.path_gl3w {
+            Gl3wPath::Single(ref path) => Gl3wPath::Single(out_dir.join(path)),
+            Gl3wPath::Separate(ref path_h, ref path_c) => Gl3wPath::Separate(out_dir.join(path_h), out_dir.join(path_c))
+        };
+        self.path_gl3w = path_gl3w;
+
+        run(self).map_err(|(stage, e)| io::Error::new(e.kind(), format!("{}: {}", stage, e)))?;
+        for path in self.input_paths() {
+            writeln!(cargo, "cargo:rerun-if-changed={}", path.display())?;
+        }
+        Ok(self.output_paths())
+    }
+
+    /// Convert generated `text` to the requested line endings, minifying it
+    /// first with `--minify`.
+    fn line_endings(&self, text: String) -> String {
+        let text = if self.minify { minify(&text) } else { text };
+        if self.crlf { text.replace("\n", "\r\n") } else { text }
+    }
+
+    /// The widths names are padded to in pointer declarations and in
+    /// `#define`s respectively.
+    fn widths(&self) -> (usize, usize) {
+        self.align.map_or((52, 45), |n| (n, n))
+    }
+
+    /// Generate each gl3w file in memory, along with where it belongs.
+    fn generate_all(&self, glcorearb_h: &str, procs: &[Proc], version: Option<(u32, u32)>) -> Vec<(PathBuf, String)> {
+        let mut files = match self.path_gl3w {
+            Gl3wPath::Single(ref path) => vec![(path.clone(), self.generate_single(glcorearb_h, procs, version))],
+            Gl3wPath::Separate(ref path_h, ref path_c) => vec![
+                (path_h.clone(), self.generate_header(glcorearb_h, procs, version)),
+                (path_c.clone(), self.generate_source(procs))
+            ]
+        };
+        if let Some(path) = self.path_internal_header() {
+            files.insert(1, (path, self.generate_internal_header(procs)));
+        }
+        if let Some(path) = self.path_pch_header() {
+            files.insert(1, (path, self.generate_pch_header(procs)));
+        }
+        files
+    }
+
+    /// Print a unified diff from each existing gl3w file to what would be
+    /// generated, with a missing file diffed as empty.
+    ///
+    /// As with `check_current` the generation date is ignored.
+    pub fn dry_diff(&self, glcorearb_h: &str, procs: &[Proc], version: Option<(u32, u32)>) -> io::Result<()> {
+        let undated = |text: &str| {
+            text.lines().filter(|l| !l.starts_with(" * date: ")).collect::<Vec<_>>().join("\n")
+        };
+
+        for (path, expected) in self.generate_all(glcorearb_h, procs, version) {
+            let mut actual = String::new();
+            let old_name = match File::open(&path) {
+                Ok(mut f) => {
+                    f.read_to_string(&mut actual)?;
+                    path.display().to_string()
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => "/dev/null".to_string(),
+                Err(e) => return Err(e)
+            };
+
+            print!("{}", diff::unified(&old_name, &path.display().to_string(),
+                                       &undated(&actual), &undated(&expected)));
+        }
+        Ok(())
+    }
+
+    /// Insert the functions of the `--append-extension` extensions into the
+    /// existing gl3w files without regenerating anything else, returning how
+    /// many were added and the files changed.
+    ///
+    /// The new lines are generated as usual and each is placed among the
+    /// existing lines of the same kind in name order, so this expects files
+    /// generated with the default `--sort-by id`. Functions already present
+    /// are skipped.
+    pub fn append_extensions(&self, glcorearb_h: &str, procs: &[Proc]) -> io::Result<(usize, Vec<PathBuf>)> {
+        let (path_h, path_c) = match self.path_gl3w {
+            Gl3wPath::Separate(ref path_h, ref path_c) => (path_h, path_c),
+            Gl3wPath::Single(_) => unreachable!("rejected by parse_args")
+        };
+        for extension in &self.append_extensions {
+            if !procs.iter().any(|p| p.block.as_ref() == Some(extension)) {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, format!(
+                    "no functions from {} found in {}", extension, self.source()
+                )));
+            }
+        }
+
+        let read = |path: &Path| -> io::Result<String> {
+            let mut contents = String::new();
+            File::open(path).and_then(|mut f| f.read_to_string(&mut contents)).map_err(|e| {
+                io::Error::new(e.kind(), format!("failed to read {}: {}", path.display(), e))
+            })?;
+            Ok(contents)
+        };
+
+        // With --split-headers gl3w.h only has its function count updated,
+        // and gl3w_pch.h only has typedefs for the compatibility profile
+        let mut paths = self.path_pch_header().into_iter().collect::<Vec<_>>();
+        paths.push(path_h.clone());
+        paths.extend(self.path_internal_header());
+        paths.push(path_c.clone());
+        let contents = paths.iter().map(|path| read(path)).collect::<io::Result<Vec<_>>>()?;
+
+        let existing = contents[paths.len() - 2].lines().filter_map(proc_line_key)
+                             .filter(|&(kind, _)| kind == ProcLine::Define)
+                             .map(|(_, name)| name)
+                             .collect::<HashSet<_>>();
+        let added = procs.iter()
+                         .filter(|p| p.block.as_ref().map_or(false, |b| self.append_extensions.contains(b)))
+                         .filter(|p| !existing.contains(p.id.as_str()))
+                         .cloned()
+                         .collect::<Vec<_>>();
+        if added.is_empty() {
+            return Ok((0, Vec::new()));
+        }
+
+        let mut generated = Vec::new();
+        if self.pch_header {
+            generated.push(self.generate_pch_header(&added));
+        }
+        generated.push(self.generate_header(glcorearb_h, &added, None));
+        if self.split_headers {
+            generated.push(self.generate_internal_header(&added));
+        }
+        generated.push(self.generate_source(&added));
+
+        for (i, path) in paths.iter().enumerate() {
+            let spliced = splice_proc_lines(&contents[i], &generated[i], added.len()).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, format!(
+                    "cannot find where to insert functions in {}; was it generated by gl3w-native?",
+                    path.display()
+                ))
+            })?;
+            if spliced != contents[i] {
+                File::create(path)?.write_all(spliced.as_bytes())?;
+            }
+        }
+        Ok((added.len(), paths))
+    }
+
+    /// Compare the functions an existing generated header `#define`s against
+    /// `procs`, returning the header checked, the functions it has which
+    /// `procs` do not and those it is missing, each sorted.
+    ///
+    /// Unlike `verify` only the function names matter, so a header
+    /// generated with different options can still be audited.
+    pub fn audit(&self, procs: &[Proc]) -> io::Result<(PathBuf, Vec<String>, Vec<String>)> {
+        let (path, contents) = self.read_existing_header()?;
+
+        let declared = contents.lines().filter_map(proc_line_key)
+                               .filter(|&(kind, _)| kind == ProcLine::Define)
+                               .map(|(_, name)| name)
+                               .collect::<HashSet<_>>();
+        if declared.is_empty() {
+            return Err(no_functions_found(&path));
+        }
+        let current = procs.iter().map(|p| p.id.as_str()).collect::<HashSet<_>>();
+
+        let mut removed = declared.difference(&current).map(|id| id.to_string()).collect::<Vec<_>>();
+        let mut added = current.difference(&declared).map(|id| id.to_string()).collect::<Vec<_>>();
+        removed.sort();
+        added.sort();
+        Ok((path, removed, added))
+    }
+
+    /// The generated header declaring the function pointers, either
+    /// `--header` or the one we would generate.
+    fn path_existing_header(&self) -> PathBuf {
+        match self.existing_header {
+            Some(ref path) => path.clone(),
+            None => match self.path_gl3w {
+                Gl3wPath::Single(ref path) => path.clone(),
+                Gl3wPath::Separate(ref path_h, _) => self.path_internal_header().unwrap_or_else(|| path_h.clone())
+            }
+        }
+    }
+
+    /// Read `path_existing_header`, returning its path as well.
+    fn read_existing_header(&self) -> io::Result<(PathBuf, String)> {
+        let path = self.path_existing_header();
+        let mut contents = String::new();
+        File::open(&path).and_then(|mut f| f.read_to_string(&mut contents)).map_err(|e| {
+            io::Error::new(e.kind(), format!("failed to read {}: {}", path.display(), e))
+        })?;
+        Ok((path, contents))
+    }
+
+    /// The procs an existing generated header declares, for
+    /// `--emit-loader-only`, in the order it declares them.
+    ///
+    /// Each OpenGL name is `#define`d to an expression naming its function
+    /// pointer, whose type is taken from the pointer's declaration. Nothing
+    /// else is known, which is all gl3w.c needs.
+    fn existing_procs(&self) -> io::Result<(PathBuf, Vec<Proc>)> {
+        let (path, contents) = self.read_existing_header()?;
+
+        let mut pfns = HashMap::new();
+        for line in contents.lines() {
+            if let Some((ProcLine::Pointer, var)) = proc_line_key(line) {
+                let pfn = line.split_whitespace().find(|w| w.starts_with("PFN") || w.starts_with("GL3W_PFN"));
+                if let Some(pfn) = pfn {
+                    pfns.insert(var, pfn);
+                }
+            }
+        }
+
+        let mut procs = Vec::new();
+        let mut seen = HashSet::new();
+        for line in contents.lines() {
+            let id = match proc_line_key(line) {
+                Some((ProcLine::Define, id)) => id,
+                _ => continue
+            };
+            // With --trace the name is defined once for each branch, only
+            // one of which names the pointer itself
+            let value = line.splitn(3, char::is_whitespace).nth(2).unwrap_or("");
+            let var = value.split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                           .find(|w| pfns.contains_key(w));
+            if let Some(var) = var {
+                if seen.insert(id) {
+                    procs.push(Proc {
+                        var: var.to_string(),
+                        pfn: pfns[var].to_string(),
+                        ..Proc::new(id)
+                    });
+                }
+            }
+        }
+
+        if procs.is_empty() {
+            return Err(no_functions_found(&path));
+        }
+        // gl.h declares it directly for the compatibility profile
+        if self.profile != Profile::Compatibility && !seen.contains("glGetIntegerv") {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+                "{} does not declare glGetIntegerv, which gl3wInit needs", path.display()
+            )));
+        }
+        Ok((path, procs))
+    }
+
+    /// Compare the gl3w files on disk against what would be generated,
+    /// returning a description of each file which differs.
+    ///
+    /// The generation date is ignored so files generated earlier with the
+    /// same input and options are still current.
+    pub fn check_current(&self, glcorearb_h: &str, procs: &[Proc], version: Option<(u32, u32)>) -> io::Result<Vec<String>> {
+        let mut stale = Vec::new();
+        for (path, expected) in self.generate_all(glcorearb_h, procs, version) {
+            let mut actual = String::new();
+            match File::open(&path) {
+                Ok(mut f) => f.read_to_string(&mut actual)?,
+                Err(ref e) if e.kind() == io::ErrorKind::NotFound => {
+                    stale.push(format!("{} is missing", path.display()));
+                    continue;
+                }
+                Err(e) => return Err(e)
+            };
+
+            let undated = |text: &str| {
+                text.lines().filter(|l| !l.starts_with(" * date: ")).map(|l| l.to_string()).collect::<Vec<_>>()
+            };
+            let (expected, actual) = (undated(&expected), undated(&actual));
+
+            let differing = expected.iter().zip(&actual).filter(|&(a, b)| a != b).count() +
+                            cmp::max(expected.len(), actual.len()) - cmp::min(expected.len(), actual.len());
+            if differing > 0 {
+                let first = expected.iter().zip(&actual).position(|(a, b)| a != b)
+                                    .unwrap_or_else(|| cmp::min(expected.len(), actual.len()));
+                stale.push(format!("{} is out of date: {} line(s) differ, starting at line {}",
+                                   path.display(), differing, first + 1));
+            }
+        }
+
+        Ok(stale)
+    }
+
+    /// A description of where glcorearb.h is read from, for messages.
+    fn source(&self) -> String {
+        if self.command == Command::LoaderOnly {
+            self.path_existing_header().display().to_string()
+        }
+        else if self.inputs.is_empty() {
+            self.url_glcorearb.clone()
+        }
+        else {
+            self.inputs.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")
+        }
+    }
+
+    /// Where `--if-changed` records the hash of the header last generated
+    /// from, next to gl3w.h.
+    fn path_state(&self) -> PathBuf {
+        match self.path_gl3w {
+            Gl3wPath::Single(ref path) | Gl3wPath::Separate(ref path, _) => path.with_file_name(".gl3w-native-state")
+        }
+    }
+
+    /// Where `--resume` records the files written so far, next to gl3w.h.
+    fn path_resume(&self) -> PathBuf {
+        match self.path_gl3w {
+            Gl3wPath::Single(ref path) | Gl3wPath::Separate(ref path, _) => path.with_file_name(".gl3w-native-resume")
+        }
+    }
+
+    /// Whether `glcorearb_h` hashes to `digest` as on the last run, and the
+    /// gl3w files generated then are still there.
+    fn is_unchanged(&self, digest: &str) -> bool {
+        let mut state = String::new();
+        if File::open(self.path_state()).and_then(|mut f| f.read_to_string(&mut state)).is_err() {
+            return false;
+        }
+
+        let exists = match self.path_gl3w {
+            Gl3wPath::Single(ref path) => path.exists(),
+            Gl3wPath::Separate(ref path_h, ref path_c) => path_h.exists() && path_c.exists()
+        };
+        exists && state.split_whitespace().next() == Some(digest)
+    }
+
+    /// The date recorded in the generated files: `SOURCE_DATE_EPOCH` if it
+    /// is set, else none with `--reproducible` and the current time
+    /// otherwise.
+    fn date(&self) -> Option<time::Tm> {
+        match self.source_date_epoch {
+            Some(epoch) => Some(time::at_utc(time::Timespec::new(epoch, 0))),
+            None if self.reproducible => None,
+            None => Some(time::now_utc())
+        }
+    }
+
+    /// Write a comment recording how a generated file was produced.
+    fn gen_metadata<T: Write>(&self, out: &mut T, procs: &[Proc]) -> io::Result<()> {
+        writeln!(out, "/* Generated by gl3w-native {}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(out, " * source: {}", self.source())?;
+        if let (false, Some(date)) = (self.no_timestamp, self.date()) {
+            writeln!(out, " * date: {}", date.rfc3339())?;
+        }
+        writeln!(out, " * procs: {}", procs.len())?;
+        writeln!(out, " */")
+    }
+
+    /// Fail if `--fail-on-warning` is set and any warning has been given,
+    /// while parsing the options or since.
+    fn check_warnings(&self) -> io::Result<()> {
+        let count = self.warnings.load(Ordering::SeqCst);
+        if !self.fail_on_warning || count == 0 {
+            return Ok(());
+        }
+        Err(io::Error::new(io::ErrorKind::Other, format!(
+            "{} warning{} given with --fail-on-warning", count, if count == 1 { "" } else { "s" }
+        )))
+    }
+
+    /// Compile the generated files with the system C compiler.
+    ///
+    /// The generated source includes `<GL/gl3w.h>` and `<GL/glcorearb.h>`,
+    /// which need not match where the files were actually written, so we
+    /// compile against a directory of forwarding headers instead.
+    ///
+    /// The cached header may be compressed, so the parsed `glcorearb_h` is
+    /// written out in full rather than forwarded to.
+    ///
+    /// If no compiler can be found a warning is printed and this succeeds.
+    pub fn verify(&self, glcorearb_h: &str) -> io::Result<()> {
+        // Each run gets its own directory so concurrent runs never compile
+        // against each other's headers
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let shim = env::temp_dir().join(format!(
+            "gl3w-native-verify-{}{:09}-{}", now.as_secs(), now.subsec_nanos(), VERIFY_RUNS.fetch_add(1, Ordering::SeqCst)
+        ));
+        let result = self.verify_in(&shim, glcorearb_h);
+        let _ = fs::remove_dir_all(&shim);
+        result
+    }
+
+    /// `verify`, with the forwarding headers written to `shim`.
+    fn verify_in(&self, shim: &Path, glcorearb_h: &str) -> io::Result<()> {
+        fs::create_dir_all(shim.join("GL"))?;
+
+        let (path_h, path_c, defines) = match self.path_gl3w {
+            Gl3wPath::Single(ref path) => (path, path, vec!["GL3W_IMPLEMENTATION"]),
+            Gl3wPath::Separate(ref path_h, ref path_c) => (path_h, path_c, vec![])
+        };
+
+        write_forwarding_header(&shim.join("GL").join(self.header_name()), path_h)?;
+
+        // A self-contained file must compile without the parsed headers
+        if !self.self_contained && self.profile == Profile::Gles3 {
+            fs::create_dir_all(shim.join("GLES3"))?;
+            File::create(shim.join("GLES3/gl3.h"))?.write_all(glcorearb_h.as_bytes())?;
+            // Their contents are part of gl3.h already, but gl3w.h still
+            // includes them
+            for url in &self.extra_headers {
+                let (dir, name) = extra_header_include(url);
+                fs::create_dir_all(shim.join(dir))?;
+                File::create(shim.join(dir).join(name))?;
+            }
+        }
+        else if !self.self_contained {
+            // For the compatibility profile the parsed header is glext.h
+            let name = if self.profile == Profile::Compatibility { "GL/glext.h" } else { "GL/glcorearb.h" };
+            File::create(shim.join(name))?.write_all(glcorearb_h.as_bytes())?;
+        }
+        if let Some(path) = self.path_internal_header() {
+            write_forwarding_header(&shim.join("GL").join(self.internal_header_name()), &path)?;
+        }
+        if let Some(path) = self.path_pch_header() {
+            write_forwarding_header(&shim.join("GL").join(self.pch_header_name()), &path)?;
+        }
+
+        let mut files = vec![(path_c.clone(), defines, self.lang)];
+        if let Some(path) = self.path_smoke_test() {
+            files.push((path.clone(), vec![], compile::Lang::C));
+        }
+
+        let compilers = (compile::Compiler::discover(compile::Lang::C), compile::Compiler::discover(compile::Lang::Cpp));
+        let include_dirs = vec![shim.to_path_buf()];
+        let results = parallel_map(self.threads, files, move |(path, defines, lang)| {
+            let compiler = if lang == compile::Lang::Cpp { &compilers.1 } else { &compilers.0 };
+            compiler.check(&path, &include_dirs, &defines)
+        });
+
+        for result in results {
+            if !result? {
+                warn(self.quiet, &self.warnings, "no C compiler found, skipping compile verification");
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the `GL_VERSION_*` and extension blocks in `header`.
+///
+/// A block is an `#ifndef GL_<NAME>` immediately followed by
+/// `#define GL_<NAME> 1`, up to its matching `#endif`. Every conditional
+/// directive is tracked so that other `#if`s nested inside (or around) a
+/// block do not end it early.
+///
+/// Returns the innermost block from each offset into `header` onwards, in
+/// order of offset.
+fn gl_blocks(header: &str) -> Vec<(usize, Option<String>)> {
+    let mut blocks = vec![(0, None)];
+    let mut stack: Vec<Option<String>> = Vec::new();
+    let mut offset = 0;
+
+    let mut lines = header.split('\n').peekable();
+    while let Some(line) = lines.next() {
+        offset += line.len() + 1;
+
+        let line = line.trim();
+        if !line.starts_with('#') {
+            continue;
+        }
+        let directive = line[1..].split_whitespace().collect::<Vec<_>>();
+
+        match directive.first().cloned() {
+            Some("ifndef") if directive.len() == 2 && directive[1].starts_with("GL_") => {
+                let name = directive[1];
+                let guard = lines.peek().map_or(false, |next| {
+                    next.split_whitespace().collect::<Vec<_>>() == ["#define", name, "1"]
+                });
+                stack.push(if guard { Some(name.to_string()) } else { None });
+            }
+            Some("if") | Some("ifdef") | Some("ifndef") => stack.push(None),
+            Some("endif") => {
+                stack.pop();
+            }
+            _ => continue
+        }
+
+        let innermost = stack.iter().rev().filter_map(|b| b.clone()).next();
+        blocks.push((offset, innermost));
+    }
+
+    blocks
+}
+
+/// Find the headers combined into `header`, from their `__gl_<name>_h_`
+/// include guards.
+///
+/// Returns the header name (e.g. `glcorearb.h`) from each offset into
+/// `header` onwards, in order of offset, in the same form as `gl_blocks`.
+fn gl_sources(header: &str) -> Vec<(usize, Option<String>)> {
+    let re = regex::Regex::new(r"(?m)^#ifndef\s+__gl_(\w+)_h_\s*$").unwrap();
+    let mut sources = vec![(0, None)];
+    sources.extend(re.captures_iter(header)
+                     .map(|e| (e.pos(0).unwrap().0, Some(format!("{}.h", &e[1])))));
+    sources
+}
+
+/// The block containing `offset`, from the result of `gl_blocks`.
+fn block_at(blocks: &[(usize, Option<String>)], offset: usize) -> Option<String> {
+    let i = match blocks.binary_search_by_key(&offset, |b| b.0) {
+        Ok(i) => i,
+        Err(i) => i - 1
+    };
+    blocks[i].1.clone()
+}
+
+/// The vendor of an extension block, e.g. `NV` for `GL_NV_fence`. Version
+/// blocks have no vendor.
+fn block_vendor(block: &str) -> Option<&str> {
+    if block.starts_with("GL_VERSION_") || block.starts_with("GL_ES_VERSION_") || !block.starts_with("GL_") {
+        return None;
+    }
+    match block[3..].split('_').next() {
+        Some("") | None => None,
+        vendor => vendor
+    }
+}
+
+/// The `--init-groups` group of `p`: 0 for core functions, 1 for ARB and KHR
+/// extensions and 2 for the other vendors'.
+fn proc_group(p: &Proc) -> usize {
+    match p.block.as_ref().and_then(|b| block_vendor(b)) {
+        None => 0,
+        Some("ARB") | Some("KHR") => 1,
+        Some(_) => 2
+    }
+}
+
+/// The extension declaring `p`, or `None` for a core function.
+fn extension_of(p: &Proc) -> Option<&str> {
+    match p.block {
+        Some(ref block) if !block.starts_with("GL_VERSION_") && !block.starts_with("GL_ES_VERSION_") => Some(block),
+        _ => None
+    }
+}
+
+/// The OpenGL version introducing a block, e.g. `(4, 0)` for
+/// `GL_VERSION_4_0`. Extension blocks have no version.
+fn block_version(block: &str) -> Option<(u32, u32)> {
+    if block.starts_with("GL_VERSION_") {
+        parse_gl_version(&block["GL_VERSION_".len()..].replace('_', "."))
+    }
+    else {
+        None
+    }
+}
+
+/// What introduced `p`, named after the block declaring it, e.g.
+/// `OpenGL 4.5` or `GL_ARB_imaging`.
+fn block_label(p: &Proc) -> String {
+    match p.block {
+        Some(ref block) => match block_version(block) {
+            Some((major, minor)) => format!("OpenGL {}.{}", major, minor),
+            None => block.clone()
+        },
+        None => "unknown".to_string()
+    }
+}
+
+/// Parse a `major.minor` version.
+fn parse_gl_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.splitn(2, '.');
+    match (parts.next().map(str::parse), parts.next().map(str::parse)) {
+        (Some(Ok(major)), Some(Ok(minor))) => Some((major, minor)),
+        _ => None
+    }
+}
+
+/// Map the uppercased form of every `gl*` identifier in `header` back to the
+/// identifier itself.
+fn gl_identifiers(header: &str) -> HashMap<String, String> {
+    let re = regex::Regex::new(r"\bgl[A-Z]\w*").unwrap();
+    re.find_iter(header)
+      .map(|(start, end)| (header[start..end].to_uppercase(), header[start..end].to_string()))
+      .collect()
+}
+
+/// The HTTP client shared by every request.
+///
+/// With `--allow-host` redirects are followed here rather than by hyper, so
+/// that every url along the way can be checked against the allowed hosts.
+#[derive(Debug)]
+struct Http {
+    client: hyper::Client,
+    allowed_hosts: Vec<String>,
+
+    /// TLS certificates are not checked
+    insecure: bool,
+
+    /// Fail every request before connecting
+    no_network: bool
+}
+
+/// Redirects followed for a single request before giving up.
+const MAX_REDIRECTS: usize = 10;
+
+impl Http {
+    /// A client which may only contact `allowed_hosts`, or any host if
+    /// there are none, and skips TLS certificate checks if `insecure`. With
+    /// `no_network` every request fails instead.
+    fn new(allowed_hosts: Vec<String>, insecure: bool, no_network: bool) -> Http {
+        // Unlike the default client, hyper's plain `Openssl` wrapper never
+        // sets a verify mode or callback, so nothing is checked
+        let mut client = if insecure {
+            let https = HttpsConnector::new(Openssl::default());
+            hyper::Client::with_connector(Pool::with_connector(Default::default(), https))
+        }
+        else {
+            hyper::Client::new()
+        };
+        if !allowed_hosts.is_empty() {
+            client.set_redirect_policy(RedirectPolicy::FollowNone);
+        }
+        Http {
+            client,
+            allowed_hosts,
+            insecure,
+            no_network
+        }
+    }
+
+    /// Make a `method` request to `url` with `headers`, also sent with each
+    /// redirected request.
+    fn send(&self, method: Method, url: &str, headers: Headers) -> hyper::Result<hyper::client::Response> {
+        if self.no_network {
+            return Err(hyper::Error::Io(io::Error::new(io::ErrorKind::PermissionDenied,
+                "network access is disabled by --no-network; use --input or populate the cache first"
+            )));
+        }
+        if self.allowed_hosts.is_empty() {
+            return self.client.request(method, url).headers(headers).send();
+        }
+
+        let mut url = hyper::Url::parse(url)?;
+        for _ in 0..MAX_REDIRECTS + 1 {
+            let host = url.host_str().unwrap_or("").to_lowercase();
+            if !self.allowed_hosts.contains(&host) {
+                return Err(hyper::Error::Io(io::Error::new(io::ErrorKind::PermissionDenied, format!(
+                    "{} is not an allowed host; allow it with --allow-host", host
+                ))));
+            }
+
+            let resp = self.client.request(method.clone(), url.clone()).headers(headers.clone()).send()?;
+            let location = match resp.headers.get::<Location>() {
+                Some(location) if resp.status.is_redirection() => location.0.clone(),
+                _ => return Ok(resp)
+            };
+            url = resp.url.join(&location)?;
+        }
+
+        Err(hyper::Error::Io(io::Error::new(io::ErrorKind::Other, format!(
+            "more than {} redirects", MAX_REDIRECTS
+        ))))
+    }
+}
+
+/// Fetch `url` into memory, treating any unsuccessful status as an error.
+///
+/// Some mirrors answer with an HTML error or captcha page instead of the
+/// header, so an HTML response is an error as well unless `allow_html`.
+///
+/// A response larger than the maximum size is an error rather than being
+/// read into memory.
+///
+/// The ETag of the response is returned too if the server sent one.
+fn download(client: &Http, url: &str, fetch: Fetch,
+            observer: Option<&Gl3wObserver>) -> io::Result<(String, Option<EntityTag>)> {
+    let mut resp = client.send(Method::Get, url, Headers::new()).map_err(|e| {
+        io::Error::new(io::ErrorKind::Other, format!("failed to fetch {}: {}", url, e))
+    })?;
+
+    if !resp.status.is_success() {
+        return Err(io::Error::new(io::ErrorKind::Other,
+            format!("failed to fetch {}: {}", url, resp.status)));
+    }
+
+    if let Some(&ContentType(Mime(TopLevel::Text, SubLevel::Html, _))) = resp.headers.get::<ContentType>() {
+        if !fetch.allow_html {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+                "{} returned an HTML page instead of a header; use --allow-html to accept it", url
+            )));
+        }
+    }
+
+    let max_size = fetch.max_size.unwrap_or(DEFAULT_MAX_SIZE);
+    let too_large = || io::Error::new(io::ErrorKind::InvalidData, format!(
+        "{} is larger than the maximum size of {} bytes; use --max-size to allow it", url, max_size
+    ));
+    let total = resp.headers.get::<ContentLength>().map(|len| len.0);
+    if total.map_or(false, |len| len > max_size) {
+        return Err(too_large());
+    }
+
+    // The length may be missing or wrong, so the read is bounded as well
+    let mut contents = Vec::new();
+    let mut buf = [0; 8192];
+    loop {
+        let n = match resp.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e)
+        };
+        if (contents.len() + n) as u64 > max_size {
+            return Err(too_large());
+        }
+        contents.extend_from_slice(&buf[..n]);
+        if let Some(observer) = observer {
+            observer.on_download_progress(url, contents.len() as u64, total);
+        }
+    }
+    if let Some(observer) = observer {
+        observer.on_download_finished(url, contents.len() as u64);
+    }
+    let contents = String::from_utf8(contents).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("{} is not valid UTF-8", url))
+    })?;
+
+    // Otherwise an empty 200 would be cached and generate an empty loader.
+    // Every header has at least an include guard
+    if contents.trim().is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("{} returned an empty body", url)));
+    }
+    if !contents.lines().any(|line| line.trim().starts_with('#')) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!(
+            "{} returned {} bytes without a single preprocessor line, which is not a header", url, contents.len()
+        )));
+    }
+
+    let etag = resp.headers.get::<ETag>().map(|etag| etag.0.clone());
+    Ok((contents, etag))
+}
+
+/// Make a `HEAD` request to `url`, falling back to a `GET` whose body is
+/// never read for servers which do not allow `HEAD`.
+fn head(client: &Http, url: &str) -> io::Result<hyper::client::Response> {
+    let failed = |e: hyper::Error| io::Error::new(io::ErrorKind::Other, format!("failed to fetch {}: {}", url, e));
+
+    let resp = client.send(Method::Head, url, Headers::new()).map_err(&failed)?;
+    if resp.status == hyper::status::StatusCode::MethodNotAllowed {
+        return client.send(Method::Get, url, Headers::new()).map_err(&failed);
+    }
+    Ok(resp)
+}
+
+/// Print the final url after redirects, status, content type and length
+/// of `url`, failing unless it is a success.
+fn probe_url(client: &Http, url: &str) -> io::Result<()> {
+    let resp = head(client, url)?;
+
+    let unknown = || "unknown".to_string();
+    println!("{}", url);
+    println!("  final url: {}", resp.url);
+    println!("  status: {}", resp.status);
+    println!("  content-type: {}", resp.headers.get::<ContentType>().map_or_else(&unknown, |t| t.to_string()));
+    println!("  content-length: {}", resp.headers.get::<ContentLength>().map_or_else(&unknown, |l| l.to_string()));
+    if client.insecure && resp.url.scheme() == "https" {
+        println!("  certificate: not verified (--insecure)");
+    }
+
+    if !resp.status.is_success() {
+        return Err(io::Error::new(io::ErrorKind::Other, format!("failed to fetch {}: {}", url, resp.status)));
+    }
+    Ok(())
+}
+
+/// Read the header cached at `path`, downloading it from `url` first if
+/// needed.
+///
+/// With `check_stale` set a cached header is checked against the remote
+/// copy. One which no longer matches its recorded checksum is downloaded
+/// again.
+fn fetch_cached(client: &Http, url: &str, path: &Path, fetch: Fetch, warnings: &AtomicUsize,
+                observer: Option<&Gl3wObserver>) -> io::Result<String> {
+    let mut corrupt = false;
+    if !fetch.no_cache && path.exists() {
+        let cached = read_header(path)?;
+        corrupt = !matches_checksum(path, &cached);
+        if corrupt {
+            warn(fetch.quiet, warnings, &format!("cached {} does not match its checksum, downloading it again", path.display()));
+        }
+        else if !fetch.revalidate || is_current(client, url, path, &cached, fetch.quiet, warnings) {
+            // Checking only ever warns
+            if fetch.check_stale {
+                check_stale(client, url, path, fetch.quiet, warnings);
+            }
+            return Ok(cached);
+        }
+    }
+
+    let downloaded = match download(client, url, fetch, observer) {
+        // The old registry location no longer serves the header, so
+        // configurations which still name it are redirected.
+        Err(ref e) if url == URL_GLCOREARB_OLD => {
+            warn(fetch.quiet, warnings, &e.to_string());
+            warn(fetch.quiet, warnings, &format!("{} has moved, retrying with {}", URL_GLCOREARB_OLD, URL_GLCOREARB));
+            download(client, URL_GLCOREARB, fetch, observer)
+        }
+
+        result => result
+    };
+
+    let (contents, etag) = match downloaded {
+        Ok(s) => s,
+
+        Err(ref e) if fetch.stale_on_error && !corrupt && path.exists() => {
+            warn(fetch.quiet, warnings, &e.to_string());
+            warn(fetch.quiet, warnings, &format!("using the cached {}, which may be outdated", path.display()));
+            return read_header(path);
+        }
+
+        Err(e) => return Err(e)
+    };
+
+    if !fetch.no_write {
+        persist_cached(path, &contents, etag)?;
+    }
+    Ok(contents)
+}
+
+/// Write a downloaded header to the cache at `path`, along with its
+/// checksum and ETag.
+fn persist_cached(path: &Path, contents: &str, etag: Option<EntityTag>) -> io::Result<()> {
+    create_parent_dir(path)?;
+    write_header(path, contents)?;
+
+    // In the format of `sha256sum`, which can check it unless compressed
+    let name = path.file_name().map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+    File::create(checksum_path(path))?.write_all(
+        format!("{}  {}\n", sha256::hex_digest(contents.as_bytes()), name).as_bytes()
+    )?;
+
+    if let Some(etag) = etag {
+        File::create(etag_path(path))?.write_all(etag.to_string().as_bytes())?;
+    }
+    Ok(())
+}
+
+/// Where the ETag of the header cached at `path` is recorded.
+fn etag_path(path: &Path) -> PathBuf {
+    let mut etag = path.as_os_str().to_owned();
+    etag.push(".etag");
+    PathBuf::from(etag)
+}
+
+/// Where the SHA-256 digest of the header cached at `path` is recorded.
+fn checksum_path(path: &Path) -> PathBuf {
+    let mut checksum = path.as_os_str().to_owned();
+    checksum.push(".sha256");
+    PathBuf::from(checksum)
+}
+
+/// Whether `cached`, the header cached at `path`, matches the digest
+/// recorded when it was downloaded. Caches from before digests were
+/// recorded have nothing to check against and always match.
+fn matches_checksum(path: &Path, cached: &str) -> bool {
+    let mut recorded = String::new();
+    if File::open(checksum_path(path)).and_then(|mut f| f.read_to_string(&mut recorded)).is_err() {
+        return true;
+    }
+    recorded.split_whitespace().next() == Some(sha256::hex_digest(cached.as_bytes()).as_str())
+}
+
+/// The ETag recorded for the header cached at `path`, if any.
+fn stored_etag(path: &Path) -> Option<EntityTag> {
+    let mut etag = String::new();
+    File::open(etag_path(path))
+        .and_then(|mut f| f.read_to_string(&mut etag)).ok()
+        .and_then(|_| etag.trim().parse::<EntityTag>().ok())
+}
+
+/// Check whether `cached`, the header cached at `path`, is the same as the
+/// one at `url` using a `HEAD` request.
+///
+/// The `Content-Length` must match, as must the ETag if one was recorded
+/// and the server sends one. If the server gives us neither there is no way
+/// to tell, so the header is treated as changed. If the request fails the
+/// cached header is used with a warning, unless `quiet`.
+fn is_current(client: &Http, url: &str, path: &Path, cached: &str, quiet: bool, warnings: &AtomicUsize) -> bool {
+    let resp = match client.send(Method::Head, url, Headers::new()) {
+        Ok(ref resp) if resp.status.is_success() => resp.headers.clone(),
+        Ok(resp) => {
+            warn(quiet, warnings, &format!("failed to revalidate {}: {}, using the cached copy", url, resp.status));
+            return true;
+        }
+        Err(e) => {
+            warn(quiet, warnings, &format!("failed to revalidate {}: {}, using the cached copy", url, e));
+            return true;
+        }
+    };
+
+    let length = resp.get::<ContentLength>().map(|length| length.0 == cached.len() as u64);
+    let etag = match (resp.get::<ETag>(), stored_etag(path)) {
+        (Some(remote), Some(stored)) => Some(remote.0.weak_eq(&stored)),
+        _ => None
+    };
+
+    match (length, etag) {
+        (None, None) => false,
+        (length, etag) => length.unwrap_or(true) && etag.unwrap_or(true)
+    }
+}
+
+/// Warn if the header cached at `path` differs from the one at `url`.
+///
+/// This only makes a conditional `HEAD` request against the ETag recorded
+/// when the header was downloaded, so nothing is fetched. Any failure to
+/// check is a warning as well, as the cached copy is still usable. Nothing
+/// is printed if `quiet`.
+fn check_stale(client: &Http, url: &str, path: &Path, quiet: bool, warnings: &AtomicUsize) {
+    let stored = match stored_etag(path) {
+        Some(etag) => etag,
+        None => {
+            warn(quiet, warnings, &format!("no ETag recorded for cached {}, cannot check if it is outdated", path.display()));
+            return;
+        }
+    };
+
+    let mut headers = Headers::new();
+    headers.set(IfNoneMatch::Items(vec![stored.clone()]));
+    let resp = match client.send(Method::Head, url, headers) {
+        Ok(resp) => resp,
+        Err(e) => {
+            warn(quiet, warnings, &format!("failed to check {}: {}", url, e));
+            return;
+        }
+    };
+
+    // Servers which ignore the condition still report the current ETag
+    let fresh = resp.status == hyper::status::StatusCode::NotModified ||
+                resp.headers.get::<ETag>().map_or(false, |etag| etag.0.weak_eq(&stored));
+
+    if !fresh {
+        warn(quiet, warnings, &format!("cached {} is outdated; run with --no-cache to refresh", path.display()));
+    }
+}
+
+/// Apply `f` to every item using at most `threads` threads, returning the
+/// results in the order of `items`.
+///
+/// With a single thread everything runs in order on the calling thread.
+fn parallel_map<A, T, F>(threads: usize, items: Vec<A>, f: F) -> Vec<T>
+    where A: Send + 'static, T: Send + 'static, F: Fn(A) -> T + Send + Sync + 'static
+{
+    if threads <= 1 || items.len() <= 1 {
+        return items.into_iter().map(f).collect();
+    }
+
+    let count = items.len();
+    let items = Arc::new(Mutex::new(items.into_iter().enumerate()));
+    let f = Arc::new(f);
+    let (tx, rx) = mpsc::channel();
+
+    let workers = (0..cmp::min(threads, count)).map(|_| {
+        let items = items.clone();
+        let f = f.clone();
+        let tx = tx.clone();
+
+        thread::spawn(move || {
+            // The lock must be released before running `f`
+            loop {
+                let item = items.lock().unwrap().next();
+                match item {
+                    Some((i, item)) => tx.send((i, f(item))).unwrap(),
+                    None => break
+                }
+            }
+        })
+    }).collect::<Vec<_>>();
+
+    drop(tx);
+    let mut results = rx.iter().collect::<Vec<_>>();
+    for worker in workers {
+        worker.join().expect("worker thread panicked");
+    }
+
+    results.sort_by_key(|&(i, _)| i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Create the directory containing `path` if it does not exist.
+///
+/// A bare file name has no parent (or an empty one) and refers to the
+/// current directory, so there is nothing to create.
+fn create_parent_dir(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) if parent != Path::new("") => fs::create_dir_all(parent),
+        _ => Ok(())
+    }
+}
+
+/// Returns true if `path` names a gzip-compressed file.
+fn is_gzip(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == "gz")
+}
+
+/// Read a header into memory, decompressing it if it ends in `.gz`.
+fn read_header(path: &Path) -> io::Result<String> {
+    let f = File::open(path)?;
+    let mut contents = String::new();
+
+    if is_gzip(path) {
+        flate2::read::GzDecoder::new(f)?.read_to_string(&mut contents)?;
+    }
+    else {
+        io::BufReader::new(f).read_to_string(&mut contents)?;
+    }
+
+    Ok(contents)
+}
+
+/// Write a header to disk, compressing it if `path` ends in `.gz`.
+fn write_header(path: &Path, contents: &str) -> io::Result<()> {
+    let f = File::create(path)?;
+
+    if is_gzip(path) {
+        let mut gz = flate2::write::GzEncoder::new(f, flate2::Compression::Default);
+        gz.write_all(contents.as_bytes())?;
+        gz.finish()?;
+    }
+    else {
+        io::BufWriter::new(f).write_all(contents.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Write a header at `at` which simply includes `target`.
+fn write_forwarding_header(at: &Path, target: &Path) -> io::Result<()> {
+    let target = fs::canonicalize(target)?;
+    let mut f = File::create(at)?;
+    writeln!(f, "#include \"{}\"", target.display())
+}
+
+/// The number of `verify` runs so far, telling apart the directories of
+/// runs started in the same instant.
+#[allow(deprecated)]
+static VERIFY_RUNS: AtomicUsize = ATOMIC_USIZE_INIT;
+
+// The warnings given on each thread, for the tests to check
+#[cfg(test)]
+thread_local!(static WARNED: std::cell::RefCell<Vec<String>> = std::cell::RefCell::new(Vec::new()));
+
+/// Print a warning on stderr unless `quiet` is set, counting it in `given`.
+fn warn(quiet: bool, given: &AtomicUsize, message: &str) {
+    given.fetch_add(1, Ordering::SeqCst);
+    #[cfg(test)]
+    WARNED.with(|warned| warned.borrow_mut().push(message.to_string()));
+    if !quiet {
+        eprintln!("warning: {}", message);
+    }
+}
+
+/// Read a `--name-map` file. Each line holds a function name and the
+/// variable name to give it, separated by whitespace. Blank lines and lines
+/// starting with `#` are ignored. Each function may only be mapped once.
+fn read_name_map(path: &Path) -> Result<HashMap<String, String>, String> {
+    let mut contents = String::new();
+    File::open(path).and_then(|mut f| f.read_to_string(&mut contents))
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+    let mut map = HashMap::new();
+    for (n, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        if words.len() != 2 {
+            return Err(format!("{}:{}: expected `<function> <name>`", path.display(), n + 1));
+        }
+        if !is_identifier(words[1]) {
+            return Err(format!("{}:{}: `{}` is not a C identifier", path.display(), n + 1, words[1]));
+        }
+        if map.insert(words[0].to_string(), words[1].to_string()).is_some() {
+            return Err(format!("{}:{}: `{}` is already mapped", path.display(), n + 1, words[0]));
+        }
+    }
+
+    Ok(map)
+}
+
+/// Read the OpenGL functions referenced in `nm` output.
+///
+/// The symbol is the last field of each line. Both `glFoo` and the
+/// `gl3wFoo` pointer a gl3w user links against count as `glFoo`. A leading
+/// underscore (as on macOS) and any `@VERSION` suffix are ignored.
+fn read_nm_symbols(path: &Path) -> Result<HashSet<String>, String> {
+    let mut contents = String::new();
+    File::open(path).and_then(|mut f| f.read_to_string(&mut contents))
+        .map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+
+    let re = regex::Regex::new(r"^_?gl(?:3w)?([A-Z]\w*)$").unwrap();
+    Ok(contents.lines()
+               .filter_map(|line| line.split_whitespace().last())
+               .map(|symbol| symbol.split('@').next().unwrap())
+               .filter_map(|symbol| re.captures(symbol).map(|e| format!("gl{}", &e[1])))
+               .collect())
+}
+
+/// Whether `name` is a valid C identifier.
+fn is_identifier(name: &str) -> bool {
+    let ident = |c: char| c == '_' || c.is_alphanumeric() && (c as u32) < 0x80;
+    name.chars().next().map_or(false, |c| ident(c) && !c.is_digit(10)) && name.chars().all(ident)
+}
+
+/// Run `command` for the argument `arg`, unless `given`, the argument
+/// choosing the command so far, chose another. Repeating an argument is
+/// not a conflict.
+fn set_command(exec: &mut Gl3wExec, given: &mut Option<String>, arg: &str, command: Command) -> Result<(), String> {
+    match *given {
+        Some(ref first) if first != arg => return Err(format!("{} cannot be used with {}", arg, first)),
+        _ => {}
+    }
+    *given = Some(arg.to_string());
+    exec.command = command;
+    Ok(())
+}
+
+/// Read the extension names listed in `path`, or on stdin if it is `-`.
+fn read_extension_list(path: &str) -> Result<HashSet<String>, String> {
+    if path == "-" {
+        let stdin = io::stdin();
+        let list = extension_list(stdin.lock());
+        list.map_err(|e| format!("failed to read stdin: {}", e))
+    }
+    else {
+        File::open(path).and_then(extension_list).map_err(|e| format!("failed to read {}: {}", path, e))
+    }
+}
+
+/// The extension names listed one per line in `reader`. Blank lines and
+/// lines starting with `#` are ignored.
+fn extension_list<R: Read>(mut reader: R) -> io::Result<HashSet<String>> {
+    let mut contents = String::new();
+    reader.read_to_string(&mut contents)?;
+    Ok(contents.lines()
+               .map(|line| line.trim())
+               .filter(|line| !line.is_empty() && !line.starts_with('#'))
+               .map(|line| line.to_string())
+               .collect())
+}
+
+/// Take the value following `flag` from `args`.
+fn flag_value<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<String, String> {
+    args.next().ok_or_else(|| format!("`{}` requires a value", flag))
+}
+
+/// The value of an include path flag as written after `#include`, quoted
+/// unless it is already quoted or in brackets.
+fn include_path<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<String, String> {
+    let path = flag_value(args, flag)?;
+    if path.is_empty() {
+        return Err(format!("{} needs a path", flag));
+    }
+    if path.starts_with('"') || path.starts_with('<') {
+        Ok(path)
+    }
+    else {
+        Ok(format!("\"{}\"", path))
+    }
+}
+
+/// The value following `flag`, compiled as a regex.
+fn flag_regex<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<regex::Regex, String> {
+    let value = flag_value(args, flag)?;
+    regex::Regex::new(&value).map_err(|e| format!("invalid {} `{}`: {}", flag, value, e))
+}
+
+/// The url of `header` at `git_ref` (a commit or tag) of the GitHub registry
+/// mirror.
+fn registry_url(git_ref: &str, header: &str) -> Result<String, String> {
+    if git_ref.is_empty() || git_ref.contains(|c: char| c.is_whitespace() || c == '?' || c == '#') {
+        return Err(format!("invalid --registry-ref `{}`", git_ref));
+    }
+    match registry_header(header) {
+        Some(("OpenGL", dir)) => Ok(format!("{}/{}/api/{}/{}", URL_REGISTRY_GITHUB, git_ref, dir, header)),
+        Some(_) => Err(format!("{} is not in the OpenGL-Registry mirror used by --registry-ref", header)),
+        None => Err(format!("unknown registry header `{}`", header))
+    }
+}
+
+/// The registry and include directory of a header served by the Khronos
+/// registry, e.g. `("OpenGL", "GLES2")` for gl2.h.
+fn registry_header(header: &str) -> Option<(&'static str, &'static str)> {
+    match header {
+        "glcorearb.h" | "glext.h" | "wglext.h" | "glxext.h" => Some(("OpenGL", "GL")),
+        "gl2.h" | "gl2ext.h" | "gl2platform.h" => Some(("OpenGL", "GLES2")),
+        "gl3.h" | "gl31.h" | "gl32.h" | "gl3platform.h" => Some(("OpenGL", "GLES3")),
+        "egl.h" | "eglext.h" | "eglplatform.h" => Some(("EGL", "EGL")),
+        "khrplatform.h" => Some(("EGL", "KHR")),
+        _ => None
+    }
+}
+
+/// The include directory and file name of the `--extra-header` at `url`,
+/// e.g. `("GLES2", "gl2ext.h")`. Headers the registry does not serve are
+/// taken to be in the directory the url names.
+fn extra_header_include(url: &str) -> (&str, &str) {
+    let mut parts = url.rsplit('/');
+    let name = parts.next().unwrap_or(url);
+    match registry_header(name) {
+        Some((_, dir)) => (dir, name),
+        None => (parts.next().unwrap_or(""), name)
+    }
+}
+
+/// The url of the registry header `header` under the registry at `base`,
+/// e.g. `https://registry.khronos.org/OpenGL/api/GL/glext.h`.
+///
+/// `base` may also name one of the registry's `api` directories (e.g.
+/// `https://my-mirror/OpenGL/api`), the other registries being its
+/// siblings.
+fn registry_header_url(base: &str, header: &str) -> Result<String, String> {
+    let (registry, dir) = registry_header(header).ok_or_else(|| format!("unknown registry header `{}`", header))?;
+    let mut base = if base.ends_with('/') { &base[..base.len() - 1] } else { base };
+    for api in &["/OpenGL/api", "/EGL/api"] {
+        if base.ends_with(api) {
+            base = &base[..base.len() - api.len()];
+        }
+    }
+    Ok(format!("{}/{}/api/{}/{}", base, registry, dir, header))
+}
+
+/// Parse a `NAME` or `NAME=VALUE` preprocessor define.
+fn parse_define(define: &str) -> Result<(String, Option<String>), String> {
+    let (name, value) = match define.find('=') {
+        Some(i) => (&define[..i], Some(define[i + 1..].to_string())),
+        None => (define, None)
+    };
+
+    if !is_identifier(name) {
+        return Err(format!("invalid define name `{}`", name));
+    }
+
+    Ok((name.to_string(), value))
+}
+
+/// Parse a `SOURCE_DATE_EPOCH`, which must be a date the generated files
+/// can record.
+fn parse_source_date_epoch(epoch: &str) -> Result<i64, String> {
+    // 9999-12-31T23:59:59Z, the last date with a four digit year. Beyond
+    // it gmtime may fail, which `time::at_utc` panics on
+    const MAX_EPOCH: i64 = 253_402_300_799;
+    match epoch.trim().parse() {
+        Ok(n) if n >= 0 && n <= MAX_EPOCH => Ok(n),
+        _ => Err(format!("invalid SOURCE_DATE_EPOCH `{}`", epoch))
+    }
+}
+
+/// Parse the command line arguments (excluding the program name).
+pub fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Gl3wExec, String> {
+    let mut exec = Gl3wExec::default();
+    let mut compress_cache = false;
+    let mut url_given = false;
+    let mut basename = None;
+    let mut upstream_layout = false;
+    let mut source_ext = None;
+    let mut registry_ref = None;
+    let mut registry_base = None;
+    let mut header_name = None;
+    let mut allowed_hosts = Vec::new();
+    let mut insecure = false;
+    let mut profile_given = false;
+    let mut no_network = false;
+    let mut command = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "list-extensions" => set_command(&mut exec, &mut command, &arg, Command::ListExtensions)?,
+            "list-procs" => set_command(&mut exec, &mut command, &arg, Command::ListProcs)?,
+            "verify" => set_command(&mut exec, &mut command, &arg, Command::Verify)?,
+            "check-url" => set_command(&mut exec, &mut command, &arg, Command::CheckUrl)?,
+            "audit" => set_command(&mut exec, &mut command, &arg, Command::Audit)?,
+            "--emit-loader-only" => set_command(&mut exec, &mut command, &arg, Command::LoaderOnly)?,
+            "--header" => exec.existing_header = Some(PathBuf::from(flag_value(&mut args, &arg)?)),
+            "--verify-compile" => exec.verify_compile = true,
+            "--no-cache" => exec.fetch.no_cache = true,
+            "--check-stale" => exec.fetch.check_stale = true,
+            "--allow-html" => exec.fetch.allow_html = true,
+            "--revalidate" => exec.fetch.revalidate = true,
+            "--no-write-glcorearb" => exec.fetch.no_write = true,
+            "--allow-stale-on-error" => exec.fetch.stale_on_error = true,
+            "--compress-cache" => compress_cache = true,
+            "--no-timestamp" => exec.no_timestamp = true,
+            "--reproducible" => exec.reproducible = true,
+            "--allow-empty" => exec.allow_empty = true,
+            "--expect-count" => {
+                let value = flag_value(&mut args, &arg)?;
+                exec.expect_count = match value.parse() {
+                    Ok(n) => Some(n),
+                    _ => return Err(format!("invalid {} `{}`", arg, value))
+                };
+            }
+            "-v" | "--verbose" => exec.verbose = true,
+            "-q" | "--quiet" | "--quiet-errors-json" => exec.quiet = true,
+            "--progress" => exec.observer = Some(Arc::new(ProgressPrinter)),
+            "--json-errors" => {}
+            "--fetch-only" => set_command(&mut exec, &mut command, &arg, Command::FetchOnly)?,
+            "--dry-diff" => set_command(&mut exec, &mut command, &arg, Command::DryDiff)?,
+            "--head-only-validate" => set_command(&mut exec, &mut command, &arg, Command::HeadValidate)?,
+            "--append-extension" => {
+                exec.append_extensions.push(flag_value(&mut args, &arg)?);
+                set_command(&mut exec, &mut command, &arg, Command::AppendExtensions)?;
+            }
+            "--print-version-only" => set_command(&mut exec, &mut command, &arg, Command::PrintVersion)?,
+            "--list-outputs" => set_command(&mut exec, &mut command, &arg, Command::ListOutputs)?,
+            "--dump-templates" => {
+                exec.template_dump_dir = Some(PathBuf::from(flag_value(&mut args, &arg)?));
+                set_command(&mut exec, &mut command, &arg, Command::DumpTemplates)?;
+            }
+            "--require-context" => exec.require_context = true,
+            "--libgl-name" => exec.libgl_name = Some(flag_value(&mut args, &arg)?),
+            "--header-only" => exec.header_only = true,
+            "--split-headers" => exec.split_headers = true,
+            "--pch-header" => exec.pch_header = true,
+            "--self-contained" => exec.self_contained = true,
+            "--khrplatform-include" => exec.khrplatform_include = Some(include_path(&mut args, &arg)?),
+            "--header-include" => exec.header_include = Some(include_path(&mut args, &arg)?),
+            "--init2" => exec.init2 = true,
+            "--init-groups" => exec.init_groups = true,
+            "--error-enum" => exec.error_enum = true,
+            "--cross-platform-includes" => exec.cross_platform_includes = true,
+            "--runtime-info" => exec.runtime_info = true,
+            "--assert-loaded" => exec.assert_loaded = true,
+            "--trace" => exec.trace = true,
+            "--export-macro" => {
+                let name = flag_value(&mut args, &arg)?;
+                if !is_identifier(&name) {
+                    return Err(format!("invalid {} `{}`", arg, name));
+                }
+                exec.export_macro = Some(name);
+            }
+            "--load-bitmap" => exec.load_bitmap = true,
+            "--emit-selftest" => exec.selftest = true,
+            "--lazy-extensions" => exec.lazy_extensions = true,
+            "--index-enum" => exec.index_enum = true,
+            "--proc-names" => exec.proc_names = true,
+            "--no-extern-c" => exec.no_extern_c = true,
+            "--annotate-versions" => exec.annotate_versions = true,
+            "--annotate-source" => exec.annotate_source = true,
+            "--group-by-version" => exec.group_by_version = true,
+            "--fail-on-warning" => exec.fail_on_warning = true,
+            "--stats" => exec.stats = Some(PathBuf::from(flag_value(&mut args, &arg)?)),
+            "--depfile" => exec.depfile = Some(PathBuf::from(flag_value(&mut args, &arg)?)),
+            "--list-filtered" => exec.list_filtered = Some(PathBuf::from(flag_value(&mut args, &arg)?)),
+            "--bench" => exec.bench = true,
+            "--if-changed" => exec.if_changed = true,
+            "--resume" => exec.resume = true,
+            "--pragma-once" => exec.pragma_once = true,
+            "--append-define" => {
+                let define = parse_define(&flag_value(&mut args, &arg)?)?;
+                exec.defines.push(define);
+            }
+            "--idempotent-init" => exec.idempotent_init = true,
+            "--refcount-init" => exec.refcount_init = true,
+            "--thread-local" => exec.thread_local = true,
+            "--static-define" => exec.static_define = true,
+            "--prune-enums" => exec.prune_enums = true,
+            "--dump-enums" => {
+                exec.outputs.push(OutputTarget::Enums(PathBuf::from(flag_value(&mut args, &arg)?)));
+            }
+            "--dump-procs" => {
+                exec.outputs.push(OutputTarget::Procs(PathBuf::from(flag_value(&mut args, &arg)?)));
+            }
+            "--json-procs-schema" => {
+                exec.outputs.push(OutputTarget::ProcsSchema(PathBuf::from(flag_value(&mut args, &arg)?)));
+            }
+            "--loader" => {
+                let name = flag_value(&mut args, &arg)?;
+                exec.loader = Loader::from_name(&name)
+                    .ok_or_else(|| format!("unknown loader `{}`", name))?;
+            }
+            "--url" => {
+                exec.url_glcorearb = flag_value(&mut args, &arg)?;
+                url_given = true;
+            }
+            "--basename" => basename = Some(flag_value(&mut args, &arg)?),
+            "--layout" => {
+                upstream_layout = match flag_value(&mut args, &arg)?.as_str() {
+                    "default" => false,
+                    "upstream" => true,
+                    layout => return Err(format!("unknown layout `{}`", layout))
+                };
+            }
+            "--lang" => {
+                exec.lang = match flag_value(&mut args, &arg)?.as_str() {
+                    "c" => compile::Lang::C,
+                    "cpp" => compile::Lang::Cpp,
+                    lang => return Err(format!("unknown language `{}`", lang))
+                };
+            }
+            "--source-ext" => {
+                let ext = flag_value(&mut args, &arg)?;
+                match ext.as_str() {
+                    "c" | "cc" | "cpp" => source_ext = Some(ext),
+                    _ => return Err(format!("unknown source extension `{}`", ext))
+                }
+            }
+            "--registry-ref" => registry_ref = Some(flag_value(&mut args, &arg)?),
+            "--registry-base" => registry_base = Some(flag_value(&mut args, &arg)?),
+            "--header-name" => header_name = Some(flag_value(&mut args, &arg)?),
+            "--output-manifest-format" => {
+                let name = flag_value(&mut args, &arg)?;
+                exec.manifest_format = ManifestFormat::from_name(&name)
+                    .ok_or_else(|| format!("unknown manifest format `{}`", name))?;
+            }
+            "--sort-by" | "--order" => {
+                let name = flag_value(&mut args, &arg)?;
+                exec.sort_by = SortKey::from_name(&name)
+                    .ok_or_else(|| format!("unknown sort key `{}`", name))?;
+            }
+            "--sort-case-insensitive" => exec.sort_case_insensitive = true,
+            "--align" => {
+                let value = flag_value(&mut args, &arg)?;
+                exec.align = Some(value.parse().map_err(|_| format!("invalid --align `{}`", value))?);
+            }
+            "--line-endings" => {
+                let value = flag_value(&mut args, &arg)?;
+                exec.crlf = match value.as_str() {
+                    "lf" => false,
+                    "crlf" => true,
+                    _ => return Err(format!("unknown line endings `{}`", value))
+                };
+            }
+            "--minify" => exec.minify = true,
+            "--profile" => {
+                let name = flag_value(&mut args, &arg)?;
+                exec.profile = Profile::from_name(&name)
+                    .ok_or_else(|| format!("unknown profile `{}`", name))?;
+                profile_given = true;
+            }
+            "--extra-header" => exec.extra_headers.push(flag_value(&mut args, &arg)?),
+            "--allow-host" => {
+                let host = flag_value(&mut args, &arg)?.to_lowercase();
+                if host.is_empty() || host.contains('/') || (host.contains(':') && !host.starts_with('[')) {
+                    return Err(format!("invalid --allow-host `{}`, expected a host name e.g. `registry.khronos.org`", host));
+                }
+                allowed_hosts.push(host);
+            }
+            "--insecure" => insecure = true,
+            "--no-network" => no_network = true,
+            "--max-size" => {
+                let value = flag_value(&mut args, &arg)?;
+                exec.fetch.max_size = match value.parse() {
+                    Ok(n) if n > 0 => Some(n),
+                    _ => return Err(format!("invalid {} `{}`", arg, value))
+                };
+            }
+            "--threads" | "--max-concurrency" => {
+                let value = flag_value(&mut args, &arg)?;
+                exec.threads = match value.parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => return Err(format!("invalid {} `{}`", arg, value))
+                };
+            }
+            "--prefix" | "--compat-prefix" => {
+                let prefix = flag_value(&mut args, &arg)?;
+                if !is_identifier(&prefix) {
+                    return Err(format!("invalid {} `{}`", arg, prefix));
+                }
+                if arg == "--prefix" {
+                    exec.prefix = prefix;
+                }
+                else {
+                    exec.compat_prefixes.push(prefix);
+                }
+            }
+            "--calling-convention" => {
+                let cc = flag_value(&mut args, &arg)?;
+                if !is_identifier(&cc) {
+                    return Err(format!("invalid --calling-convention `{}`", cc));
+                }
+                exec.calling_convention = Some(cc);
+            }
+            "--patch" => exec.patch = Some(PathBuf::from(flag_value(&mut args, &arg)?)),
+            "--name-map" => {
+                exec.name_map = read_name_map(Path::new(&flag_value(&mut args, &arg)?))?;
+            }
+            "--proc-regex" => {
+                let re = flag_regex(&mut args, &arg)?;
+                if re.captures_len() < 2 {
+                    return Err(format!("--proc-regex `{}` must capture the function name", re));
+                }
+                exec.proc_regex = Some(re);
+            }
+            "--match" => exec.matches.push(flag_regex(&mut args, &arg)?),
+            "--no-deprecated" => exec.no_deprecated = true,
+            "--exclude-match" => exec.exclude_matches.push(flag_regex(&mut args, &arg)?),
+            "--extensions-from" => {
+                exec.extensions = Some(read_extension_list(&flag_value(&mut args, &arg)?)?);
+            }
+            "--from-nm" => {
+                exec.used = Some(read_nm_symbols(Path::new(&flag_value(&mut args, &arg)?))?);
+            }
+            "--since" => {
+                let value = flag_value(&mut args, &arg)?;
+                exec.since = Some(parse_gl_version(&value)
+                    .ok_or_else(|| format!("invalid --since `{}`, expected e.g. `4.0`", value))?);
+            }
+            "--vendor" => {
+                let vendor = flag_value(&mut args, &arg)?;
+                if vendor.is_empty() || !vendor.chars().all(|c| c.is_alphanumeric()) {
+                    return Err(format!("invalid --vendor `{}`, expected e.g. `NV`", vendor));
+                }
+                exec.vendors.push(vendor);
+            }
+            "--input" => {
+                exec.inputs.push(PathBuf::from(flag_value(&mut args, &arg)?));
+            }
+            "--emit-smoke-test" => {
+                exec.outputs.push(OutputTarget::SmokeTest(PathBuf::from(flag_value(&mut args, &arg)?)));
+            }
+            "--cmake-package" => exec.cmake_package = Some(PathBuf::from(flag_value(&mut args, &arg)?)),
+            "--stdout" => {
+                let name = flag_value(&mut args, &arg)?;
+                exec.stdout = Some(Artifact::from_name(&name).ok_or_else(|| format!("unknown artifact `{}`", name))?);
+            }
+            "--compile-commands" => {
+                exec.outputs.push(OutputTarget::CompileCommands(PathBuf::from(flag_value(&mut args, &arg)?)));
+            }
+            "--manifest" => {
+                exec.outputs.push(OutputTarget::Manifest(PathBuf::from(flag_value(&mut args, &arg)?)));
+            }
+            "--changelog" => exec.changelog = Some(PathBuf::from(flag_value(&mut args, &arg)?)),
+            "--dump-typedefs" => {
+                exec.outputs.push(OutputTarget::Typedefs(PathBuf::from(flag_value(&mut args, &arg)?)));
+            }
+            _ => return Err(format!("unrecognized option `{}`", arg))
+        }
+    }
+
+    let cpp = exec.lang == compile::Lang::Cpp;
+    if basename.is_some() || source_ext.is_some() || upstream_layout || cpp {
+        let basename = basename.unwrap_or_else(|| "gl3w".to_string());
+        if basename.is_empty() || basename.contains(|c: char| c == '/' || c == '\\') {
+            return Err(format!("invalid --basename `{}`", basename));
+        }
+        let source_ext = source_ext.unwrap_or_else(|| if cpp { "cpp" } else { "c" }.to_string());
+        let header_ext = if cpp { "hpp" } else { "h" };
+
+        // Upstream gl3w puts the header beside glcorearb.h
+        let header_dir = if upstream_layout { "include/GL" } else { "src" };
+        exec.path_gl3w = Gl3wPath::Separate(
+            PathBuf::from(format!("{}/{}.{}", header_dir, basename, header_ext)),
+            PathBuf::from(format!("src/{}.{}", basename, source_ext))
+        );
+        if upstream_layout {
+            exec.path_glcorearb = PathBuf::from("include/GL/glcorearb.h");
+        }
+    }
+
+    exec.fetch.quiet = exec.quiet;
+
+    if exec.changelog.is_some() && exec.path_manifest().is_none() {
+        return Err("--changelog requires --manifest".to_string());
+    }
+
+    if header_name.is_some() && url_given {
+        return Err("--header-name cannot be used with --url".to_string());
+    }
+
+    // WebGL 2 is OpenGL ES 3, most desktop functions have no equivalent
+    if exec.loader == Loader::Emscripten {
+        if !profile_given {
+            exec.profile = Profile::Gles3;
+        }
+        else if exec.profile != Profile::Gles3 {
+            warn(exec.quiet, &exec.warnings, "--loader emscripten with a desktop profile, functions WebGL lacks will be NULL");
+        }
+    }
+    if exec.profile == Profile::Gles3 {
+        match header_name.as_ref().and_then(|h| registry_header(h)) {
+            Some((_, "GLES3")) | None => {}
+            Some(_) => return Err("--profile gles3 requires an OpenGL ES 3 --header-name".to_string())
+        }
+    }
+    let header_name = header_name.unwrap_or_else(|| {
+        if exec.profile == Profile::Gles3 { "gl3.h" } else { "glcorearb.h" }.to_string()
+    });
+
+    if let Some(git_ref) = registry_ref {
+        if url_given {
+            return Err("--registry-ref cannot be used with --url".to_string());
+        }
+        if registry_base.is_some() {
+            return Err("--registry-ref cannot be used with --registry-base".to_string());
+        }
+        exec.url_glcorearb = registry_url(&git_ref, &header_name)?;
+        exec.url_glext = registry_url(&git_ref, "glext.h")?;
+    }
+    else if let Some(base) = registry_base {
+        if url_given {
+            return Err("--registry-base cannot be used with --url".to_string());
+        }
+        exec.url_glcorearb = registry_header_url(&base, &header_name)?;
+        exec.url_glext = registry_header_url(&base, "glext.h")?;
+    }
+    else if !url_given {
+        exec.url_glcorearb = registry_header_url(URL_REGISTRY, &header_name)?;
+    }
+
+    // Other headers are cached where they would be included from
+    if header_name != "glcorearb.h" {
+        let (_, dir) = registry_header(&header_name).unwrap();
+        exec.path_glcorearb = PathBuf::from(format!("include/{}/{}", dir, header_name));
+    }
+
+    if exec.libgl_name.is_some() && exec.loader != Loader::Dlopen {
+        return Err("--libgl-name requires --loader dlopen".to_string());
+    }
+
+    // Only these include it themselves, glcorearb.h keeps its own include
+    if exec.khrplatform_include.is_some() && !exec.self_contained &&
+       !exec.outputs.iter().any(|o| match *o { OutputTarget::Typedefs(_) => true, _ => false }) {
+        return Err("--khrplatform-include requires --self-contained or --dump-typedefs".to_string());
+    }
+
+    if exec.split_headers {
+        if exec.header_only || exec.self_contained {
+            return Err("--split-headers cannot be used with a single file".to_string());
+        }
+        if let Gl3wPath::Single(_) = exec.path_gl3w {
+            return Err("--split-headers cannot be used with a single file".to_string());
+        }
+    }
+
+    if exec.pch_header {
+        if exec.header_only || exec.self_contained {
+            return Err("--pch-header cannot be used with a single file".to_string());
+        }
+        if let Gl3wPath::Single(_) = exec.path_gl3w {
+            return Err("--pch-header cannot be used with a single file".to_string());
+        }
+    }
+
+    if exec.lang == compile::Lang::Cpp {
+        if exec.header_only || exec.self_contained {
+            return Err("--lang cpp cannot be used with a single file".to_string());
+        }
+        if exec.idempotent_init {
+            return Err("--lang cpp cannot be used with --idempotent-init".to_string());
+        }
+        // The destructor releases what the constructor loaded
+        exec.refcount_init = true;
+    }
+
+    // Nothing would be fetched
+    if exec.command == Command::FetchOnly && !exec.inputs.is_empty() {
+        return Err("--fetch-only cannot be used with --input".to_string());
+    }
+
+    if exec.header_only || exec.self_contained {
+        let path = match exec.path_gl3w {
+            Gl3wPath::Separate(ref path_h, _) => Some(path_h.clone()),
+            Gl3wPath::Single(_) => None
+        };
+        if let Some(path) = path {
+            exec.path_gl3w = Gl3wPath::Single(path);
+        }
+    }
+
+    if let Ok(epoch) = env::var("SOURCE_DATE_EPOCH") {
+        exec.source_date_epoch = Some(parse_source_date_epoch(&epoch)?);
+    }
+
+    if exec.resume {
+        if let Gl3wPath::Single(_) = exec.path_gl3w {
+            return Err("--resume cannot be used with a single file".to_string());
+        }
+        // A file dated now never matches the one the failed run wrote
+        if !exec.no_timestamp && !exec.reproducible && exec.source_date_epoch.is_none() {
+            return Err("--resume requires --no-timestamp, --reproducible or SOURCE_DATE_EPOCH".to_string());
+        }
+    }
+
+    if exec.command == Command::LoaderOnly {
+        if let Gl3wPath::Single(_) = exec.path_gl3w {
+            return Err("--emit-loader-only cannot be used with a single file".to_string());
+        }
+    }
+
+    if exec.header_include.is_some() {
+        if let Gl3wPath::Single(_) = exec.path_gl3w {
+            return Err("--header-include cannot be used with a single file".to_string());
+        }
+    }
+
+    if exec.export_macro.is_some() {
+        if exec.header_only {
+            return Err("--export-macro cannot be used with --header-only".to_string());
+        }
+        // Thread-local variables cannot be imported from a DLL
+        if exec.thread_local {
+            return Err("--export-macro cannot be used with --thread-local".to_string());
+        }
+    }
+
+    if let Some(artifact) = exec.stdout {
+        if let (Artifact::Source, &Gl3wPath::Single(_)) = (artifact, &exec.path_gl3w) {
+            return Err("--stdout source cannot be used with single-file output".to_string());
+        }
+        // Both read the generated files back
+        if exec.verify_compile {
+            return Err("--stdout cannot be used with --verify-compile".to_string());
+        }
+        if exec.cmake_package.is_some() {
+            return Err("--stdout cannot be used with --cmake-package".to_string());
+        }
+    }
+
+    // Both replace gl3wInit
+    if exec.refcount_init && exec.idempotent_init {
+        return Err("--refcount-init cannot be used with --idempotent-init".to_string());
+    }
+
+    // Header-only pointers are always static
+    if exec.static_define && exec.header_only {
+        return Err("--static-define cannot be used with --header-only".to_string());
+    }
+
+    if exec.init_groups {
+        // Both choose what load_procs loads
+        if exec.init2 {
+            return Err("--init-groups cannot be used with --init2".to_string());
+        }
+        if exec.lazy_extensions {
+            return Err("--init-groups cannot be used with --lazy-extensions".to_string());
+        }
+        // gl3wInitGroups would bypass the bookkeeping of gl3wInit
+        if exec.idempotent_init {
+            return Err("--init-groups cannot be used with --idempotent-init".to_string());
+        }
+        if exec.refcount_init {
+            return Err("--init-groups cannot be used with --refcount-init".to_string());
+        }
+    }
+
+    // The self-test counts the functions the bitmap records
+    if exec.selftest {
+        exec.load_bitmap = true;
+    }
+    // The names are looked up by index
+    if exec.proc_names {
+        exec.index_enum = true;
+    }
+
+    if exec.command == Command::AppendExtensions {
+        if let Gl3wPath::Single(_) = exec.path_gl3w {
+            return Err("--append-extension cannot be used with a single file".to_string());
+        }
+        // The indices of every later function would change
+        if exec.load_bitmap {
+            return Err("--append-extension cannot be used with --load-bitmap".to_string());
+        }
+        if exec.index_enum {
+            return Err("--append-extension cannot be used with --index-enum".to_string());
+        }
+        if exec.lazy_extensions {
+            return Err("--append-extension cannot be used with --lazy-extensions".to_string());
+        }
+        // The new functions would not be reset
+        if exec.refcount_init {
+            return Err("--append-extension cannot be used with --refcount-init".to_string());
+        }
+        // New declarations are inserted in name order
+        if exec.group_by_version {
+            return Err("--append-extension cannot be used with --group-by-version".to_string());
+        }
+    }
+
+    if compress_cache {
+        for path in &mut [&mut exec.path_glcorearb, &mut exec.path_glext] {
+            if !is_gzip(path) {
+                let mut gz = path.clone().into_os_string();
+                gz.push(".gz");
+                **path = PathBuf::from(gz);
+            }
+        }
+    }
+
+    if insecure {
+        warn(exec.quiet, &exec.warnings, "--insecure disables TLS certificate verification, \
+                                          downloaded headers may have been tampered with");
+    }
+    if no_network {
+        // Each of these always makes a request
+        if exec.fetch.no_cache {
+            return Err("--no-network cannot be used with --no-cache".to_string());
+        }
+        if exec.fetch.revalidate {
+            return Err("--no-network cannot be used with --revalidate".to_string());
+        }
+        if exec.fetch.check_stale {
+            return Err("--no-network cannot be used with --check-stale".to_string());
+        }
+        if exec.command == Command::CheckUrl || exec.command == Command::HeadValidate {
+            return Err("--no-network cannot check urls".to_string());
+        }
+    }
+    if !allowed_hosts.is_empty() || insecure || no_network {
+        exec.client = Arc::new(Http::new(allowed_hosts, insecure, no_network));
+    }
+
+    Ok(exec)
+}
+
+/// The error for an existing header without any generated functions.
+fn no_functions_found(path: &Path) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!(
+        "no functions found in {}; was it generated by gl3w-native?", path.display()
+    ))
+}
+
+/// The kinds of per-function lines in generated gl3w files.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ProcLine {
+    /// `typedef void (APIENTRYP GL3W_PFNGLCULLFACEPROC) (GLenum mode);`, with
+    /// `--calling-convention` as `(CC *GL3W_PFNGLCULLFACEPROC)`
+    Typedef,
+
+    /// `extern PFNGLCULLFACEPROC gl3wCullFace;`, or without `extern` in
+    /// gl3w.c
+    Pointer,
+
+    /// `#define glCullFace gl3wCullFace`, possibly wrapped by
+    /// `GL3W_ASSERT_LOADED`
+    Define,
+
+    /// `gl3wCullFace = (PFNGLCULLFACEPROC) get_proc("glCullFace");`
+    Load
+}
+
+/// The kind of a per-function generated line and the name lines of that
+/// kind are sorted by, or `None` for any other line.
+fn proc_line_key(line: &str) -> Option<(ProcLine, &str)> {
+    let words = line.split_whitespace().collect::<Vec<_>>();
+    if words.is_empty() {
+        return None;
+    }
+
+    if words[0] == "typedef" {
+        return words.iter().map(|w| if w.starts_with('*') { &w[1..] } else { w })
+                    .find(|w| w.starts_with("GL3W_PFN"))
+                    .and_then(|name| name.split(')').next())
+                    .map(|name| (ProcLine::Typedef, typedef_name(name)));
+    }
+
+    if words.len() >= 3 && words[0] == "#define" && words[1].starts_with("gl") &&
+       words[1].chars().nth(2).map_or(false, |c| c.is_uppercase()) {
+        return Some((ProcLine::Define, words[1]));
+    }
+
+    if words.len() > 2 && words[1] == "=" && (words[2].starts_with("(PFN") || words[2].starts_with("(GL3W_PFN")) &&
+       line.ends_with("\");") {
+        return line.rfind("(\"").map(|i| (ProcLine::Load, &line[i + 2..line.len() - 3]));
+    }
+
+    // Only our own storage classes, or `extern` and an `--export-macro`,
+    // which leaves out the loader's statics
+    let pfn = words.iter().position(|w| w.starts_with("PFN") || w.starts_with("GL3W_PFN"));
+    match pfn {
+        Some(i) if words[..i].iter().all(|&w| w == "extern" || w == "GL3W_THREAD_LOCAL") ||
+                   (i == 2 && words[0] == "extern") => {
+            words.get(i + 1).and_then(|var| {
+                if var.ends_with(';') { Some((ProcLine::Pointer, &var[..var.len() - 1])) } else { None }
+            })
+        }
+        _ => None
+    }
+}
+
+/// The uppercase function name in a `PFN*PROC` typedef name, which sorts
+/// like the function names themselves.
+fn typedef_name(pfn: &str) -> &str {
+    let name = if pfn.starts_with("GL3W_") { &pfn[5..] } else { pfn };
+    let name = if name.starts_with("PFN") { &name[3..] } else { name };
+    if name.ends_with("PROC") { &name[..name.len() - 4] } else { name }
+}
+
+/// Insert each per-function line of `generated` into `existing`, before
+/// the first line of the same kind sorting after it or else after the last.
+/// The function count in the metadata comment goes up by `added`.
+///
+/// Returns `None` if `existing` has no lines of a kind to insert.
+fn splice_proc_lines(existing: &str, generated: &str, added: usize) -> Option<String> {
+    let mut lines = existing.lines().map(|l| l.to_string()).collect::<Vec<_>>();
+
+    for line in &mut lines {
+        let count = if line.starts_with(" * procs: ") { line[10..].parse::<usize>().ok() } else { None };
+        if let Some(count) = count {
+            *line = format!(" * procs: {}", count + added);
+            break;
+        }
+    }
+
+    for line in generated.lines() {
+        let (kind, name) = match proc_line_key(line) {
+            Some(key) => key,
+            None => continue
+        };
+
+        let same_kind = lines.iter().enumerate()
+            .filter_map(|(i, l)| match proc_line_key(l) {
+                Some((k, n)) if k == kind => Some((i, n > name)),
+                _ => None
+            })
+            .collect::<Vec<_>>();
+
+        let at = match same_kind.iter().find(|&&(_, after)| after) {
+            Some(&(i, _)) => i,
+            None => match same_kind.last() {
+                Some(&(i, _)) => i + 1,
+                None => return None
+            }
+        };
+        lines.insert(at, line.to_string());
+    }
+
+    let newline = if existing.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut spliced = lines.join(newline);
+    if existing.ends_with('\n') {
+        spliced.push_str(newline);
+    }
+    Some(spliced)
+}
+
+/// Run a generator against an in-memory buffer.
+fn gen_string<F: FnOnce(&mut Vec<u8>) -> io::Result<()>>(f: F) -> String {
+    let mut out = Vec::new();
+    f(&mut out).expect("writing to a Vec cannot fail");
+    String::from_utf8(out).expect("generated output is not UTF-8")
+}
+
+/// Strip the comments, blank lines and indentation from generated C
+/// `text`, and collapse other runs of whitespace to a single space.
+///
+/// Whitespace in string and character literals is kept. Line breaks are
+/// kept too, as preprocessor directives end with them, except inside
+/// comments, which the preprocessor also treats as a single space.
+fn minify(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut quote = None;
+    let mut space = false;
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            out.push(c);
+            if c == '\\' {
+                if let Some(escaped) = chars.next() {
+                    out.push(escaped);
+                }
+            }
+            else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+
+        match c {
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in &mut chars {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+                space = true;
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.peek().map_or(false, |&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            '\n' => {
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                space = false;
+            }
+            ' ' | '\t' | '\r' => space = true,
+            c => {
+                if space && !out.is_empty() && !out.ends_with('\n') {
+                    out.push(' ');
+                }
+                space = false;
+                if c == '"' || c == '\'' {
+                    quote = Some(c);
+                }
+                out.push(c);
+            }
+        }
+    }
+
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// Generate gl3w.h from a list of procs.
+fn gen_gl3w_h<T: Write>(out: &mut T, exec: &Gl3wExec, glcorearb_h: &str, procs: &[Proc],
+                        version: Option<(u32, u32)>) -> io::Result<()>
+{
+    let profile = exec.profile;
+
+    for &(ref name, ref value) in &exec.defines {
+        match *value {
+            Some(ref value) => writeln!(out, "#define {} {}", name, value)?,
+            None => writeln!(out, "#define {}", name)?
+        }
+    }
+
+    write!(out, "{}", include_str!("template/gl3w.preamble.c"))?;
+    if exec.pragma_once {
+        writeln!(out, "#pragma once\n")?;
+    }
+    else {
+        writeln!(out, "#ifndef __gl3w_h_\n#define __gl3w_h_\n")?;
+    }
+    if exec.self_contained {
+        // Everything we parsed replaces the include of it
+        let include = format!("#include <{}/{}>\n", profile.include_dir(), profile.header());
+        let glcorearb_h = match exec.khrplatform_include {
+            Some(ref khrplatform) => glcorearb_h.replace(KHRPLATFORM_INCLUDE, &format!("#include {}", khrplatform)),
+            None => glcorearb_h.to_string()
+        };
+        write!(out, "{}", exec.profile_template().replace(&include, &format!("{}\n", glcorearb_h)))?;
+    }
+    else if exec.pch_header {
+        writeln!(out, "#include <GL/{}>\n", exec.pch_header_name())?;
+    }
+    else {
+        write!(out, "{}", exec.profile_includes())?;
+    }
+    if exec.thread_local {
+        write!(out, "{}", include_str!("template/gl3w.thread_local.h"))?;
+    }
+    if let Some(ref export) = exec.export_macro {
+        write!(out, "{}", include_str!("template/gl3w.export.h").replace("GL3W_API", export))?;
+    }
+    if exec.header_only {
+        write!(out, "{}", exec.extern_c(&exec.error_enum(include_str!("template/gl3w.api.inline.h"))))?;
+        if exec.idempotent_init || exec.refcount_init {
+            write!(out, "{}", exec.error_enum(include_str!("template/gl3w.api.reinit.inline.h")))?;
+        }
+        if exec.refcount_init {
+            write!(out, "{}", include_str!("template/gl3w.api.shutdown.inline.h"))?;
+        }
+        if exec.init2 {
+            write!(out, "{}", exec.error_enum(include_str!("template/gl3w.api.init2.inline.h")))?;
+        }
+        if exec.init_groups {
+            write!(out, "{}", exec.error_enum(include_str!("template/gl3w.api.groups.inline.h")))?;
+        }
+        if exec.has_runtime_info(procs) {
+            write!(out, "{}", include_str!("template/gl3w.api.runtime.inline.h"))?;
+        }
+    }
+    else {
+        write!(out, "{}", exec.extern_c(&exec.exported(&exec.error_enum(include_str!("template/gl3w.api.h")))))?;
+        if exec.idempotent_init || exec.refcount_init {
+            write!(out, "{}", exec.exported(&exec.error_enum(include_str!("template/gl3w.api.reinit.h"))))?;
+        }
+        if exec.refcount_init {
+            write!(out, "{}", exec.exported(include_str!("template/gl3w.api.shutdown.h")))?;
+        }
+        if exec.init2 {
+            write!(out, "{}", exec.exported(&exec.error_enum(include_str!("template/gl3w.api.init2.h"))))?;
+        }
+        if exec.init_groups {
+            write!(out, "{}", exec.exported(&exec.error_enum(include_str!("template/gl3w.api.groups.h"))))?;
+        }
+        if exec.has_runtime_info(procs) {
+            write!(out, "{}", exec.exported(include_str!("template/gl3w.api.runtime.h")))?;
+        }
+    }
+    if exec.split_headers {
+        // The function pointers are declared by gl3w_internal.h
+        writeln!(out, "")?;
+    }
+    else {
+        gen_gl3w_procs_h(out, exec, procs)?;
+    }
+
+    writeln!(out, "/* Version of gl3w-native which generated this file */")?;
+    writeln!(out, "#define GL3W_NATIVE_GENERATOR_VERSION \"{}\"\n", env!("CARGO_PKG_VERSION"))?;
+
+    if let Some((major, minor)) = version {
+        writeln!(out, "/* Highest OpenGL version declared by {} */", profile.header())?;
+        writeln!(out, "#define GL3W_GL_VERSION_MAJOR {}", major)?;
+        writeln!(out, "#define GL3W_GL_VERSION_MINOR {}\n", minor)?;
+    }
+
+    write!(out, "{}", exec.extern_c(include_str!("template/gl3w.footer.h")))?;
+    if exec.lang == compile::Lang::Cpp {
+        let loader = include_str!("template/gl3w.loader.hpp");
+        if exec.error_enum {
+            write!(out, "{}", loader.replace("int ", "enum gl3w_error ").replace("== 0", "== GL3W_OK"))?;
+        }
+        else {
+            write!(out, "{}", loader)?;
+        }
+    }
+    if !exec.pragma_once {
+        writeln!(out, "#endif\n")?;
+    }
+    Ok(())
+}
+
+/// Generate gl3w_internal.h for `--split-headers`, with everything about
+/// the function pointers left out of the public gl3w.h.
+fn gen_gl3w_internal_h<T: Write>(out: &mut T, exec: &Gl3wExec, procs: &[Proc]) -> io::Result<()>
+{
+    write!(out, "{}", include_str!("template/gl3w.preamble.c"))?;
+    writeln!(out, "\n#ifndef __gl3w_internal_h_\n#define __gl3w_internal_h_\n")?;
+    writeln!(out, "#include <GL/{}>\n", exec.header_name())?;
+    write!(out, "{}", exec.extern_c(EXTERN_C_BEGIN))?;
+
+    gen_gl3w_procs_h(out, exec, procs)?;
+
+    write!(out, "{}", exec.extern_c(include_str!("template/gl3w.footer.h")))?;
+    writeln!(out, "#endif\n")?;
+    Ok(())
+}
+
+/// Generate gl3w_pch.h for `--pch-header`, with the includes and typedefs
+/// gl3w.h needs, which only change along with the parsed headers.
+fn gen_gl3w_pch_h<T: Write>(out: &mut T, exec: &Gl3wExec, procs: &[Proc]) -> io::Result<()>
+{
+    write!(out, "{}", include_str!("template/gl3w.preamble.c"))?;
+    writeln!(out, "\n#ifndef __gl3w_pch_h_\n#define __gl3w_pch_h_\n")?;
+    write!(out, "{}", exec.profile_includes())?;
+
+    if exec.own_typedefs() {
+        gen_own_typedefs(out, exec, procs)?;
+        writeln!(out, "")?;
+    }
+
+    writeln!(out, "#endif")?;
+    Ok(())
+}
+
+/// Generate our own `PFN*PROC` typedefs, for the compatibility profile or
+/// `--calling-convention`.
+fn gen_own_typedefs<T: Write>(out: &mut T, exec: &Gl3wExec, procs: &[Proc]) -> io::Result<()>
+{
+    for p in procs {
+        if let Some((ref ret, ref params)) = p.proto {
+            match exec.calling_convention {
+                Some(ref cc) => writeln!(out, "typedef {} ({} *{}) ({});", ret, cc, p.pfn, params)?,
+                None => writeln!(out, "typedef {} (APIENTRYP {}) ({});", ret, p.pfn, params)?
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Generate the typedefs, pointers and `#define`s for `procs` in gl3w.h.
+fn gen_gl3w_procs_h<T: Write>(out: &mut T, exec: &Gl3wExec, procs: &[Proc]) -> io::Result<()>
+{
+    writeln!(out, "\n/* OpenGL functions */")?;
+
+    if exec.own_typedefs() && !exec.pch_header {
+        gen_own_typedefs(out, exec, procs)?;
+        writeln!(out, "")?;
+    }
+
+    // Header-only pointers are private to each translation unit like the
+    // functions using them
+    let storage = match (exec.header_only, exec.thread_local, exec.export_macro.as_ref()) {
+        (true, false, _) => "static".to_string(),
+        (true, true, _) => "static GL3W_THREAD_LOCAL".to_string(),
+        (false, false, None) => "extern".to_string(),
+        (false, true, _) => "extern GL3W_THREAD_LOCAL".to_string(),
+        (false, false, Some(export)) => format!("extern {}", export)
+    };
+    let storage = if exec.static_define {
+        let static_storage = if exec.thread_local { "static GL3W_THREAD_LOCAL" } else { "static" };
+        writeln!(out, "#if defined(GL3W_STATIC)\n#define GL3W_STORAGE {}\n#else\n#define GL3W_STORAGE {}\n#endif\n",
+                 static_storage, storage)?;
+        "GL3W_STORAGE".to_string()
+    }
+    else {
+        storage
+    };
+    let (pfn_width, define_width) = exec.widths();
+
+    // Versions in order, then extensions by name. The sort is stable, so
+    // each group keeps the order of `procs`
+    let mut declared = procs.iter().collect::<Vec<_>>();
+    if exec.group_by_version {
+        declared.sort_by_key(|p| match p.block.as_ref().map(|b| (block_version(b), b)) {
+            Some((Some(version), _)) => (0, Some(version), None),
+            Some((None, block)) => (1, None, Some(block)),
+            None => (2, None, None)
+        });
+    }
+
+    let mut group = None;
+    for p in declared {
+        if exec.group_by_version && group.as_ref() != Some(&block_label(p)) {
+            if group.is_some() {
+                writeln!(out, "")?;
+            }
+            writeln!(out, "/* {} */", block_label(p))?;
+            group = Some(block_label(p));
+        }
+
+        let mut notes = Vec::new();
+        if exec.annotate_versions {
+            notes.push(block_label(p));
+        }
+        if exec.annotate_source {
+            notes.push(format!("from {}", p.source.as_ref().map_or("an unknown header", |s| s.as_str())));
+        }
+
+        if !notes.is_empty() {
+            writeln!(out, "{} {:<w$} {:<48} /* {} */", storage, p.pfn, format!("{};", p.var), notes.join(", "),
+                     w = pfn_width)?;
+        }
+        else {
+            writeln!(out, "{} {:<w$} {};", storage, p.pfn, p.var, w = pfn_width)?;
+        }
+    }
+
+    writeln!(out, "")?;
+
+    if exec.assert_loaded {
+        write!(out, "{}", include_str!("template/gl3w.assert_loaded.h"))?;
+    }
+    let targets = procs.iter().map(|p| if exec.assert_loaded {
+        format!("GL3W_ASSERT_LOADED({}, \"{}\")", p.var, p.id)
+    }
+    else {
+        p.var.clone()
+    }).collect::<Vec<_>>();
+
+    if exec.trace {
+        write!(out, "{}", include_str!("template/gl3w.trace.h"))?;
+        writeln!(out, "#ifdef GL3W_TRACE")?;
+        for (p, target) in procs.iter().zip(&targets) {
+            match trace_wrapper(p, target) {
+                Some(wrapper) => {
+                    writeln!(out, "{}", wrapper)?;
+                    writeln!(out, "#define {:<w$} {}", p.id, trace_name(p), w = define_width)?;
+                }
+                None => writeln!(out, "#define {:<w$} {}", p.id, target, w = define_width)?
+            }
+        }
+        writeln!(out, "#else")?;
+    }
+    for (p, target) in procs.iter().zip(&targets) {
+        writeln!(out, "#define {:<w$} {}", p.id, target, w = define_width)?;
+    }
+    if exec.trace {
+        writeln!(out, "#endif")?;
+    }
+
+    writeln!(out, "")?;
+
+    let aliases = exec.compat_prefixes.iter()
+        .flat_map(|prefix| procs.iter().map(move |p| (format!("{}{}", prefix, &p.id[2..]), &p.var)))
+        .filter(|&(ref alias, var)| alias != var)
+        .collect::<Vec<_>>();
+    if !aliases.is_empty() {
+        writeln!(out, "/* Compatibility names for the function pointers */")?;
+        for (alias, var) in aliases {
+            writeln!(out, "#define {:<w$} {}", alias, var, w = define_width)?;
+        }
+
+        writeln!(out, "")?;
+    }
+
+    if exec.load_bitmap || exec.index_enum {
+        writeln!(out, "/* Indices of the generated functions, in the order they are declared */")?;
+        writeln!(out, "enum gl3w_proc_index {{")?;
+        for p in procs {
+            writeln!(out, "\t{},", proc_index(p))?;
+        }
+        writeln!(out, "\tGL3W_PROC_COUNT\n}};\n")?;
+    }
+
+    if exec.proc_names {
+        writeln!(out, "/* The name of the function at each index, e.g. \"glClear\" */")?;
+        if exec.header_only {
+            gen_proc_names(out, "static ", procs)?;
+        }
+        else {
+            writeln!(out, "{}", exec.exported("extern const char *const gl3w_proc_names[GL3W_PROC_COUNT];\n"))?;
+        }
+    }
+
+    if exec.load_bitmap {
+        writeln!(out, "/* Whether the function at `index` was found by the last gl3wInit */")?;
+        let storage = if exec.header_only { "static inline " } else { "" };
+        writeln!(out, "{}", exec.exported(&format!("{}int gl3wProcLoaded(int index);\n", storage)))?;
+    }
+
+    if exec.lazy_extensions {
+        writeln!(out, "/* Load the functions of extension `ext`, e.g. \"GL_ARB_sparse_buffer\", once the")?;
+        writeln!(out, " * context is known to support it. Returns -1 if `ext` has no functions. */")?;
+        let storage = if exec.header_only { "static inline " } else { "" };
+        writeln!(out, "{}", exec.exported(&format!("{}int gl3wLoadExtension(const char *ext);\n", storage)))?;
+    }
+
+    if exec.selftest {
+        writeln!(out, "/* The fraction of the generated functions found by the last gl3wInit */")?;
+        let storage = if exec.header_only { "static inline " } else { "" };
+        writeln!(out, "{}", exec.exported(&format!("{}double gl3wSelfTest(void);\n", storage)))?;
+    }
+    Ok(())
+}
+
+/// The start and end of the `extern "C"` block in the generated headers,
+/// left out with `--no-extern-c`.
+const EXTERN_C_BEGIN: &str = "#ifdef __cplusplus\nextern \"C\" {\n#endif\n";
+const EXTERN_C_END: &str = "#ifdef __cplusplus\n}\n#endif\n";
+
+/// How the registry headers include `khrplatform.h`.
+const KHRPLATFORM_INCLUDE: &str = "#include <KHR/khrplatform.h>";
+
+/// The gl3w functions and helpers returning a status, given as they are
+/// declared after the return type.
+const STATUS_FUNCTIONS: &[&str] = &[
+    "gl3wInit(void)", "gl3wInit2(GL3WGetProcAddressProc proc)", "gl3wInitGroups(unsigned groups)", "gl3wReinit(void)", "load(void)", "parse_version(void)"
+];
+
+/// The statuses returned in the templates and their `enum gl3w_error`
+/// replacements.
+const STATUS_RETURNS: &[(&str, &str)] = &[
+    ("\tif (!glGetIntegerv)\n\t\treturn -1;", "\tif (!glGetIntegerv)\n\t\treturn GL3W_ERROR_INIT;"),
+    ("\tif (version.major < 3)\n\t\treturn -1;\n\treturn 0;",
+     "\tif (version.major < 3)\n\t\treturn GL3W_ERROR_OPENGL_VERSION;\n\treturn GL3W_OK;"),
+    ("\topen_libgl();\n", "\topen_libgl();\n\tif (!libgl_opened())\n\t\treturn GL3W_ERROR_LIBRARY_OPEN;\n"),
+    ("\t\treturn -2;", "\t\treturn GL3W_ERROR_NO_CONTEXT;"),
+    ("\tint res = load();", "\tenum gl3w_error res = load();"),
+    ("loaded = res == 0;", "loaded = res == GL3W_OK;"),
+    ("\tif (loaded)\n\t\treturn 0;", "\tif (loaded)\n\t\treturn GL3W_OK;"),
+    ("\tint res;\n", "\tenum gl3w_error res;\n"),
+    ("\t\trefcount++;\n\t\treturn 0;", "\t\trefcount++;\n\t\treturn GL3W_OK;"),
+    ("\tif (res == 0)\n\t\trefcount = 1;", "\tif (res == GL3W_OK)\n\t\trefcount = 1;"),
+    ("\tif (res == 0 && refcount == 0)", "\tif (res == GL3W_OK && refcount == 0)")
+];
+
+/// Every built-in template by its path under `src/template`, for
+/// `--dump-templates`.
+const TEMPLATES: &[(&str, &str)] = &[
+    ("gl3w.api.c", include_str!("template/gl3w.api.c")),
+    ("gl3w.api.groups.h", include_str!("template/gl3w.api.groups.h")),
+    ("gl3w.api.groups.inline.h", include_str!("template/gl3w.api.groups.inline.h")),
+    ("gl3w.api.h", include_str!("template/gl3w.api.h")),
+    ("gl3w.api.init2.h", include_str!("template/gl3w.api.init2.h")),
+    ("gl3w.api.init2.inline.h", include_str!("template/gl3w.api.init2.inline.h")),
+    ("gl3w.api.inline.h", include_str!("template/gl3w.api.inline.h")),
+    ("gl3w.api.reinit.h", include_str!("template/gl3w.api.reinit.h")),
+    ("gl3w.api.reinit.inline.h", include_str!("template/gl3w.api.reinit.inline.h")),
+    ("gl3w.api.runtime.h", include_str!("template/gl3w.api.runtime.h")),
+    ("gl3w.api.runtime.inline.h", include_str!("template/gl3w.api.runtime.inline.h")),
+    ("gl3w.api.shutdown.h", include_str!("template/gl3w.api.shutdown.h")),
+    ("gl3w.api.shutdown.inline.h", include_str!("template/gl3w.api.shutdown.inline.h")),
+    ("gl3w.assert_loaded.h", include_str!("template/gl3w.assert_loaded.h")),
+    ("gl3w.error.h", include_str!("template/gl3w.error.h")),
+    ("gl3w.export.h", include_str!("template/gl3w.export.h")),
+    ("gl3w.footer.h", include_str!("template/gl3w.footer.h")),
+    ("gl3w.init.c", include_str!("template/gl3w.init.c")),
+    ("gl3w.init.context.c", include_str!("template/gl3w.init.context.c")),
+    ("gl3w.init2.c", include_str!("template/gl3w.init2.c")),
+    ("gl3w.load_bitmap.c", include_str!("template/gl3w.load_bitmap.c")),
+    ("gl3w.load_extension.c", include_str!("template/gl3w.load_extension.c")),
+    ("gl3w.loader.hpp", include_str!("template/gl3w.loader.hpp")),
+    ("gl3w.preamble.c", include_str!("template/gl3w.preamble.c")),
+    ("gl3w.refcount.c", include_str!("template/gl3w.refcount.c")),
+    ("gl3w.reinit.c", include_str!("template/gl3w.reinit.c")),
+    ("gl3w.selftest.c", include_str!("template/gl3w.selftest.c")),
+    ("gl3w.smoke.main.c", include_str!("template/gl3w.smoke.main.c")),
+    ("gl3w.thread_local.h", include_str!("template/gl3w.thread_local.h")),
+    ("gl3w.trace.h", include_str!("template/gl3w.trace.h")),
+    ("gl3w.version.c", include_str!("template/gl3w.version.c")),
+    ("gl3w.version.runtime.c", include_str!("template/gl3w.version.runtime.c")),
+    ("context/dlopen.c", include_str!("template/context/dlopen.c")),
+    ("context/emscripten.c", include_str!("template/context/emscripten.c")),
+    ("context/glfw.c", include_str!("template/context/glfw.c")),
+    ("context/macos.c", include_str!("template/context/macos.c")),
+    ("context/native.c", include_str!("template/context/native.c")),
+    ("context/sdl2.c", include_str!("template/context/sdl2.c")),
+    ("context/win32.c", include_str!("template/context/win32.c")),
+    ("loader/dlopen.c", include_str!("template/loader/dlopen.c")),
+    ("loader/emscripten.c", include_str!("template/loader/emscripten.c")),
+    ("loader/glfw.c", include_str!("template/loader/glfw.c")),
+    ("loader/macos.c", include_str!("template/loader/macos.c")),
+    ("loader/native.c", include_str!("template/loader/native.c")),
+    ("loader/sdl2.c", include_str!("template/loader/sdl2.c")),
+    ("loader/win32.c", include_str!("template/loader/win32.c")),
+    ("opened/dlopen.c", include_str!("template/opened/dlopen.c")),
+    ("opened/emscripten.c", include_str!("template/opened/emscripten.c")),
+    ("opened/glfw.c", include_str!("template/opened/glfw.c")),
+    ("opened/macos.c", include_str!("template/opened/macos.c")),
+    ("opened/native.c", include_str!("template/opened/native.c")),
+    ("opened/sdl2.c", include_str!("template/opened/sdl2.c")),
+    ("opened/win32.c", include_str!("template/opened/win32.c")),
+    ("profile/compatibility.cross.h", include_str!("template/profile/compatibility.cross.h")),
+    ("profile/compatibility.h", include_str!("template/profile/compatibility.h")),
+    ("profile/core.cross.h", include_str!("template/profile/core.cross.h")),
+    ("profile/core.h", include_str!("template/profile/core.h")),
+    ("profile/gles3.h", include_str!("template/profile/gles3.h")),
+    ("smoke/glfw.c", include_str!("template/smoke/glfw.c")),
+    ("smoke/native.c", include_str!("template/smoke/native.c")),
+    ("smoke/sdl2.c", include_str!("template/smoke/sdl2.c"))
+];
+
+/// The functions gl3w calls itself, which `--trace` does not log.
+const UNTRACED_PROCS: &[&str] = &["glGetIntegerv", "glGetString"];
+
+/// The name of the `--trace` wrapper for `p`, e.g. `gl3w_trace_glCullFace`.
+fn trace_name(p: &Proc) -> String {
+    format!("gl3w_trace_{}", p.id)
+}
+
+/// A `--trace` wrapper logging a call to `p` then forwarding it to
+/// `target`, or `None` if `p` has no prototype or a parameter is unnamed.
+fn trace_wrapper(p: &Proc, target: &str) -> Option<String> {
+    let (ret, params) = match p.proto {
+        Some((ref ret, ref params)) => (ret.trim(), params.trim()),
+        None => return None
+    };
+
+    let mut args = Vec::new();
+    if params != "void" && !params.is_empty() {
+        for param in params.split(',') {
+            let param = param.trim();
+            let start = param.rfind(|c: char| !(c.is_alphanumeric() || c == '_')).map_or(0, |i| i + 1);
+            // A parameter which is only a type has no name to forward
+            if start == 0 || start == param.len() {
+                return None;
+            }
+            args.push(&param[start..]);
+        }
+    }
+
+    let call = format!("{}({})", target, args.join(", "));
+    let call = if ret == "void" { call } else { format!("return {}", call) };
+    let sep = if ret.ends_with('*') { "" } else { " " };
+    Some(format!("static inline {}{}{}({}) {{ GL3W_TRACE_CALL(\"{}\"); {}; }}",
+                 ret, sep, trace_name(p), params, p.id, call))
+}
+
+/// The `gl3w_proc_index` enumerator for `p`, e.g. `GL3W_PROC_CULLFACE`.
+///
+/// These are unique as the `PFN*PROC` typedef names are.
+fn proc_index(p: &Proc) -> String {
+    format!("GL3W_PROC_{}", p.id[2..].to_uppercase())
+}
+
+/// Define `gl3w_proc_names`, in the order of `enum gl3w_proc_index`.
+fn gen_proc_names<T: Write>(out: &mut T, storage: &str, procs: &[Proc]) -> io::Result<()>
+{
+    writeln!(out, "{}const char *const gl3w_proc_names[GL3W_PROC_COUNT] = {{", storage)?;
+    for p in procs {
+        writeln!(out, "\t\"{}\",", p.id)?;
+    }
+    writeln!(out, "}};\n")?;
+    Ok(())
+}
+
+/// Generate gl3w.c from a list of procs.
+fn gen_gl3w_c<T: Write>(out: &mut T, exec: &Gl3wExec, procs: &[Proc]) -> io::Result<()>
+{
+    let loader = exec.loader;
+
+    write!(out, "{}", include_str!("template/gl3w.preamble.c"))?;
+    if let Gl3wPath::Single(_) = exec.path_gl3w {
+        // The declarations are already above it in the same file, which
+        // may not be reachable as <GL/gl3w.h>
+    }
+    else if let Some(ref include) = exec.header_include {
+        writeln!(out, "#include {}\n", include)?;
+    }
+    else if exec.split_headers {
+        writeln!(out, "#include <GL/{}>\n", exec.internal_header_name())?;
+    }
+    else {
+        writeln!(out, "#include <GL/{}>\n", exec.header_name())?;
+    }
+    if let Some(ref name) = exec.libgl_name {
+        writeln!(out, "#define GL3W_LIBGL_NAME {}\n", c_string(name))?;
+    }
+    if exec.assert_loaded {
+        // gl3w itself checks for functions which failed to load
+        writeln!(out, "#undef GL3W_ASSERT_LOADED\n#define GL3W_ASSERT_LOADED(proc, name) (proc)\n")?;
+    }
+    // Nor are its own calls traced, as it checks the pointers themselves
+    let untraced = procs.iter().filter(|p| exec.trace && UNTRACED_PROCS.contains(&p.id.as_str())).collect::<Vec<_>>();
+    if !untraced.is_empty() {
+        writeln!(out, "#ifdef GL3W_TRACE")?;
+        for p in &untraced {
+            writeln!(out, "#undef {}\n#define {} {}", p.id, p.id, p.var)?;
+        }
+        writeln!(out, "#endif\n")?;
+    }
+    writeln!(out, "{}", loader.template())?;
+    if exec.error_enum {
+        write!(out, "{}", loader.opened_template())?;
+    }
+    if exec.has_runtime_info(procs) {
+        if !exec.header_only {
+            writeln!(out, "struct gl3w_version gl3wVersion;\n")?;
+        }
+        write!(out, "{}", exec.error_enum(include_str!("template/gl3w.version.runtime.c")))?;
+    }
+    else {
+        write!(out, "{}", exec.error_enum(include_str!("template/gl3w.version.c")))?;
+    }
+    let init = if exec.require_context {
+        write!(out, "{}", loader.context_template())?;
+        include_str!("template/gl3w.init.context.c")
+    }
+    else {
+        include_str!("template/gl3w.init.c")
+    };
+
+    if exec.idempotent_init {
+        // The usual gl3wInit does the loading for gl3wReinit, and the
+        // public gl3wInit only calls that the first time
+        write!(out, "{}", exec.error_enum(&init.replace("int gl3wInit(void)", "static int load(void)")))?;
+        let reinit = exec.error_enum(include_str!("template/gl3w.reinit.c"));
+        if exec.thread_local {
+            write!(out, "{}", reinit.replace("static int loaded;", "static GL3W_THREAD_LOCAL int loaded;"))?;
+        }
+        else {
+            write!(out, "{}", reinit)?;
+        }
+    }
+    else if exec.refcount_init {
+        // As above, with gl3wShutdown undoing the matching gl3wInit and
+        // gl3wReinit loading regardless of the count
+        write!(out, "{}", exec.error_enum(&init.replace("int gl3wInit(void)", "static int load(void)")))?;
+        let refcount = exec.error_enum(include_str!("template/gl3w.refcount.c"));
+        if exec.thread_local {
+            write!(out, "{}", refcount.replace("static int refcount;", "static GL3W_THREAD_LOCAL int refcount;"))?;
+        }
+        else {
+            write!(out, "{}", refcount)?;
+        }
+    }
+    else {
+        write!(out, "{}", exec.error_enum(init))?;
+    }
+    if exec.init_groups {
+        // gl3wInit loads every group
+        writeln!(out, "static void load_procs_groups(unsigned groups);\n")?;
+        writeln!(out, "static void load_procs(void)\n{{\n\tload_procs_groups(GL3W_GROUP_ALL);\n}}\n")?;
+        let groups = init.replace("int gl3wInit(void)", "int gl3wInitGroups(unsigned groups)")
+                         .replace("load_procs();", "load_procs_groups(groups);");
+        write!(out, "{}", exec.error_enum(&groups))?;
+    }
+    write!(out, "{}", include_str!("template/gl3w.api.c"))?;
+    if exec.init2 {
+        write!(out, "{}", exec.error_enum(include_str!("template/gl3w.init2.c")))?;
+    }
+
+    if !exec.header_only {
+        // Otherwise the header's declarations define them
+        if exec.static_define {
+            writeln!(out, "#if !defined(GL3W_STATIC)")?;
+        }
+        let storage = if exec.thread_local { "GL3W_THREAD_LOCAL " } else { "" };
+        for p in procs {
+            writeln!(out, "{}{:<w$} {};", storage, p.pfn, p.var, w = exec.widths().0)?;
+        }
+        if exec.static_define {
+            writeln!(out, "#endif")?;
+        }
+
+        writeln!(out, "")?;
+    }
+
+    if exec.proc_names && !exec.header_only {
+        gen_proc_names(out, "", procs)?;
+    }
+
+    if exec.load_bitmap {
+        let bitmap = include_str!("template/gl3w.load_bitmap.c");
+        if exec.thread_local {
+            write!(out, "{}", bitmap.replace("static unsigned char", "static GL3W_THREAD_LOCAL unsigned char"))?;
+        }
+        else {
+            write!(out, "{}", bitmap)?;
+        }
+    }
+    if exec.selftest {
+        write!(out, "{}", include_str!("template/gl3w.selftest.c"))?;
+    }
+
+    // With gl3wInit2 the resolver is passed in, and `load_procs` forwards
+    // the loader's own
+    let resolver = if exec.init2 {
+        writeln!(out, "static void load_procs_from(GL3WGetProcAddressProc proc)\n{{")?;
+        "proc"
+    }
+    else if exec.init_groups {
+        writeln!(out, "static void load_procs_groups(unsigned groups)\n{{")?;
+        "get_proc"
+    }
+    else {
+        writeln!(out, "static void load_procs(void)\n{{")?;
+        "get_proc"
+    };
+
+    let load = |out: &mut T, p: &Proc, resolver: &str, indent: &str| -> io::Result<()> {
+        writeln!(out, r#"{}{} = ({}) {}("{}");"#, indent, p.var, p.pfn, resolver, p.id)?;
+        if exec.load_bitmap {
+            writeln!(out, "{}set_loaded({}, (GL3WglProc) {});", indent, proc_index(p), p.var)?;
+        }
+        Ok(())
+    };
+
+    // Extension functions are loaded separately by gl3wLoadExtension
+    let mut extensions: Vec<(&str, Vec<&Proc>)> = Vec::new();
+    let mut groups: Vec<(&str, Vec<&Proc>)> = vec![("CORE", vec![]), ("ARB", vec![]), ("VENDOR", vec![])];
+    for p in procs {
+        match extension_of(p) {
+            Some(ext) if exec.lazy_extensions => match extensions.iter().position(|e| e.0 == ext) {
+                Some(i) => extensions[i].1.push(p),
+                None => extensions.push((ext, vec![p]))
+            },
+            // gl3wInit needs glGetIntegerv to parse the version
+            _ if exec.init_groups && p.id != "glGetIntegerv" => groups[proc_group(p)].1.push(p),
+            _ => load(out, p, resolver, "    ")?
+        }
+    }
+    for &(group, ref procs) in groups.iter().filter(|g| !g.1.is_empty()) {
+        writeln!(out, "    if (groups & GL3W_GROUP_{}) {{", group)?;
+        for p in procs {
+            load(out, p, resolver, "        ")?;
+        }
+        writeln!(out, "    }}")?;
+    }
+
+    writeln!(out, "}}")?;
+
+    if exec.lazy_extensions {
+        extensions.sort_by(|a, b| a.0.cmp(b.0));
+        writeln!(out, "\n#include <string.h>\n\nstatic int load_extension_procs(const char *ext)\n{{")?;
+        for &(ext, ref procs) in &extensions {
+            writeln!(out, "    if (!strcmp(ext, \"{}\")) {{", ext)?;
+            for p in procs {
+                load(out, p, "get_proc", "        ")?;
+            }
+            writeln!(out, "        return 0;\n    }}")?;
+        }
+        writeln!(out, "    return -1;\n}}\n")?;
+        write!(out, "{}", include_str!("template/gl3w.load_extension.c"))?;
+    }
+
+    if exec.refcount_init {
+        writeln!(out, "\nstatic void reset_procs(void)\n{{")?;
+        for p in procs {
+            writeln!(out, "    {} = NULL;", p.var)?;
+            if exec.load_bitmap {
+                writeln!(out, "    set_loaded({}, NULL);", proc_index(p))?;
+            }
+        }
+        writeln!(out, "}}")?;
+    }
+
+    // The rest of a single file's translation unit checks them again
+    if let Gl3wPath::Single(_) = exec.path_gl3w {
+        if exec.assert_loaded {
+            writeln!(out, "\n#undef GL3W_ASSERT_LOADED")?;
+            write!(out, "{}", include_str!("template/gl3w.assert_loaded.h"))?;
+        }
+        if !untraced.is_empty() {
+            writeln!(out, "\n#ifdef GL3W_TRACE")?;
+            for p in &untraced {
+                writeln!(out, "#undef {}\n#define {} {}", p.id, p.id, trace_name(p))?;
+            }
+            writeln!(out, "#endif")?;
+        }
+    }
+    Ok(())
+}
+
+/// Generate a header defining the extracted `GL_*` constants.
+fn gen_enums_h<T: Write>(out: &mut T, enums: &[Enum]) -> io::Result<()>
+{
+    write!(out, "{}", include_str!("template/gl3w.preamble.c"))?;
+    writeln!(out, "\n#ifndef __gl3w_enums_h_\n#define __gl3w_enums_h_\n")?;
+
+    for e in enums {
+        writeln!(out, "#define {:<45} {}", e.0, e.1)?;
+    }
+
+    writeln!(out, "\n#endif")?;
+    Ok(())
+}
+
+/// Generate a standalone header declaring the given typedefs.
+///
+/// Anything the typedefs themselves depend on (`khrplatform.h` types and the
+/// `APIENTRY` calling convention) is pulled in as needed.
+fn gen_typedefs_h<T: Write>(out: &mut T, exec: &Gl3wExec, typedefs: &[Typedef]) -> io::Result<()>
+{
+    write!(out, "{}", include_str!("template/gl3w.preamble.c"))?;
+    writeln!(out, "\n#ifndef __gl3w_typedefs_h_\n#define __gl3w_typedefs_h_\n")?;
+
+    if typedefs.iter().any(|t| t.1.contains("APIENTRY")) {
+        writeln!(out, "#if defined(_WIN32) && !defined(APIENTRY)\n#define APIENTRY __stdcall\n#endif")?;
+        writeln!(out, "#ifndef APIENTRY\n#define APIENTRY\n#endif\n")?;
+    }
+
+    if typedefs.iter().any(|t| t.1.contains("khronos_")) {
+        writeln!(out, "#include {}\n", exec.khrplatform_include.as_ref().map_or("<KHR/khrplatform.h>", |s| s.as_str()))?;
+    }
+
+    for t in typedefs {
+        writeln!(out, "{}", t.1)?;
+    }
+
+    writeln!(out, "\n#endif")?;
+    Ok(())
+}
+
+/// Generate a JSON array of the extracted `GL_*` constants.
+///
+/// Values are kept as strings since they may carry C suffixes (`ull`).
+fn gen_enums_json<T: Write>(out: &mut T, enums: &[Enum]) -> io::Result<()>
+{
+    writeln!(out, "[")?;
+
+    for (i, e) in enums.iter().enumerate() {
+        let sep = if i + 1 < enums.len() { "," } else { "" };
+        writeln!(out, r#"  {{ "name": "{}", "value": "{}" }}{}"#, e.0, e.1, sep)?;
+    }
+
+    writeln!(out, "]")?;
+    Ok(())
+}
+
+/// Generate a JSON array of the generated functions, with the name, pointer
+/// variable and pointer type of each.
+fn gen_procs_json<T: Write>(out: &mut T, procs: &[Proc]) -> io::Result<()>
+{
+    writeln!(out, "[")?;
+
+    for (i, p) in procs.iter().enumerate() {
+        let sep = if i + 1 < procs.len() { "," } else { "" };
+        writeln!(out, r#"  {{ "id": "{}", "var": "{}", "pfn": "{}" }}{}"#, p.id, p.var, p.pfn, sep)?;
+    }
+
+    writeln!(out, "]")?;
+    Ok(())
+}
+
+/// Generate a JSON Schema for `gen_procs_json`.
+///
+/// The pointer types are constrained to the prefix this run declares them
+/// with, so a dump from another configuration fails to validate.
+fn gen_procs_schema<T: Write>(out: &mut T, exec: &Gl3wExec) -> io::Result<()>
+{
+    let pfn = if exec.own_typedefs() { "^GL3W_PFN[A-Z0-9_]+PROC$" } else { "^PFN[A-Z0-9_]+PROC$" };
+
+    writeln!(out, r#"{{
+  "$schema": "http://json-schema.org/draft-07/schema#",
+  "title": "gl3w-native functions",
+  "description": "The functions generated by gl3w-native {}, as written by --dump-procs",
+  "type": "array",
+  "items": {{
+    "type": "object",
+    "properties": {{
+      "id": {{ "type": "string", "pattern": "^gl\\w+$", "description": "The OpenGL function name" }},
+      "var": {{ "type": "string", "pattern": "^[A-Za-z_]\\w*$", "description": "The name of its function pointer" }},
+      "pfn": {{ "type": "string", "pattern": {}, "description": "The type of its function pointer" }}
+    }},
+    "required": ["id", "var", "pfn"],
+    "additionalProperties": false
+  }}
+}}"#, env!("CARGO_PKG_VERSION"), json_string(pfn))?;
+    Ok(())
+}
+
+/// Generate a manifest of the source and generated functions in
+/// `--output-manifest-format`.
+fn gen_manifest<T: Write>(out: &mut T, exec: &Gl3wExec, procs: &[Proc]) -> io::Result<()>
+{
+    match exec.manifest_format {
+        ManifestFormat::Text => {
+            writeln!(out, "# Generated by gl3w-native {}", env!("CARGO_PKG_VERSION"))?;
+            writeln!(out, "source {}", exec.source())?;
+            for p in procs {
+                writeln!(out, "{}", p.id)?;
+            }
+        }
+
+        ManifestFormat::Json => {
+            writeln!(out, "{{")?;
+            writeln!(out, r#"  "generator": "gl3w-native {}","#, env!("CARGO_PKG_VERSION"))?;
+            writeln!(out, r#"  "source": {},"#, json_string(&exec.source()))?;
+            writeln!(out, r#"  "functions": ["#)?;
+            for (i, p) in procs.iter().enumerate() {
+                let sep = if i + 1 < procs.len() { "," } else { "" };
+                writeln!(out, r#"    "{}"{}"#, p.id, sep)?;
+            }
+            writeln!(out, "  ]\n}}")?;
+        }
+
+        // JSON strings are valid TOML basic strings
+        ManifestFormat::Toml => {
+            writeln!(out, "# Generated by gl3w-native {}", env!("CARGO_PKG_VERSION"))?;
+            writeln!(out, "source = {}", json_string(&exec.source()))?;
+            writeln!(out, "functions = [")?;
+            for p in procs {
+                writeln!(out, r#"    "{}","#, p.id)?;
+            }
+            writeln!(out, "]")?;
+        }
+    }
+    Ok(())
+}
+
+/// Read the functions listed by the manifest at `path`, if there is one.
+///
+/// The format is told from the contents, so changing
+/// `--output-manifest-format` still compares against the last manifest.
+fn read_manifest(path: &Path) -> io::Result<Option<Vec<String>>> {
+    let mut contents = String::new();
+    match File::open(path) {
+        Ok(mut f) => f.read_to_string(&mut contents)?,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e)
+    };
+
+    let lines = contents.lines().map(|line| line.trim()).collect::<Vec<_>>();
+
+    // JSON and TOML list the functions one per line in a `functions` array
+    if let Some(start) = lines.iter().position(|&l| l == r#""functions": ["# || l == "functions = [") {
+        return Ok(Some(lines[start + 1..].iter()
+                                         .take_while(|l| !l.starts_with(']'))
+                                         .map(|l| l.trim_matches(|c| c == '"' || c == ',').to_string())
+                                         .filter(|l| !l.is_empty())
+                                         .collect()));
+    }
+
+    Ok(Some(lines.into_iter()
+                 .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with("source "))
+                 .map(|line| line.to_string())
+                 .collect()))
+}
+
+/// Generate a JSON summary of a run for dashboards.
+fn gen_stats_json<T: Write>(out: &mut T, exec: &Gl3wExec, glcorearb_h: &str, procs: &[Proc],
+                            version: Option<(u32, u32)>, duration_ms: u64) -> io::Result<()>
+{
+    // Functions declared under a `GL_VERSION_x_y` block, the rest belong to
+    // extensions
+    let core = procs.iter()
+        .filter(|p| p.block.as_ref().map_or(false, |b| b.starts_with("GL_VERSION_")))
+        .count();
+    let version = match version {
+        Some((major, minor)) => format!(r#""{}.{}""#, major, minor),
+        None => "null".to_string()
+    };
+
+    writeln!(out, "{{")?;
+    writeln!(out, r#"  "procs": {},"#, procs.len())?;
+    writeln!(out, r#"  "core_procs": {},"#, core)?;
+    writeln!(out, r#"  "extension_procs": {},"#, procs.len() - core)?;
+    writeln!(out, r#"  "gl_version": {},"#, version)?;
+    writeln!(out, r#"  "source": {},"#, json_string(&exec.source()))?;
+    writeln!(out, r#"  "content_hash": "fnv1a64:{:016x}","#, fnv1a64(glcorearb_h.as_bytes()))?;
+    writeln!(out, r#"  "duration_ms": {}"#, duration_ms)?;
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+/// Generate a Makefile rule making every one of `targets` depend on every
+/// one of `prerequisites`, as read by Make and Ninja.
+///
+/// Each prerequisite also gets an empty rule, as with `gcc -MP`, so deleting
+/// a cached header is not an error for Make.
+fn gen_depfile<T: Write>(out: &mut T, targets: &[PathBuf], prerequisites: &[PathBuf]) -> io::Result<()>
+{
+    let escape = |path: &PathBuf| {
+        path.display().to_string().replace(' ', "\\ ").replace('#', "\\#").replace('$', "$$")
+    };
+    let targets = targets.iter().map(&escape).collect::<Vec<_>>();
+    let prerequisites = prerequisites.iter().map(&escape).collect::<Vec<_>>();
+
+    write!(out, "{}:", targets.join(" "))?;
+    for prerequisite in &prerequisites {
+        write!(out, " \\\n  {}", prerequisite)?;
+    }
+    writeln!(out, "")?;
+    for prerequisite in &prerequisites {
+        writeln!(out, "\n{}:", prerequisite)?;
+    }
+    Ok(())
+}
+
+/// Generate a `gl3wConfig.cmake` defining `gl3w::gl3w`, built from
+/// `sources` in the package's `src` directory.
+fn gen_cmake_config<T: Write>(out: &mut T, exec: &Gl3wExec, sources: &[String]) -> io::Result<()>
+{
+    writeln!(out, "# Generated by gl3w-native. Add this directory to CMAKE_PREFIX_PATH (or set")?;
+    writeln!(out, "# gl3w_DIR to it), then find_package(gl3w) and link against gl3w::gl3w.")?;
+    match exec.loader {
+        Loader::Sdl2 => writeln!(out, "# The loader calls SDL_GL_GetProcAddress, so SDL2 must be linked too.")?,
+        Loader::Glfw => writeln!(out, "# The loader calls glfwGetProcAddress, so GLFW must be linked too.")?,
+        _ => {}
+    }
+    writeln!(out, "")?;
+    writeln!(out, "if(TARGET gl3w::gl3w)\n  return()\nendif()\n")?;
+
+    // Without a source everything is in the header
+    if sources.is_empty() {
+        writeln!(out, "add_library(gl3w INTERFACE)")?;
+        writeln!(out, r#"target_include_directories(gl3w INTERFACE "${{CMAKE_CURRENT_LIST_DIR}}/include")"#)?;
+        writeln!(out, "target_link_libraries(gl3w INTERFACE ${{CMAKE_DL_LIBS}})")?;
+    }
+    else {
+        writeln!(out, "add_library(gl3w STATIC")?;
+        for source in sources {
+            writeln!(out, r#"  "${{CMAKE_CURRENT_LIST_DIR}}/src/{}""#, source)?;
+        }
+        writeln!(out, ")")?;
+        writeln!(out, r#"target_include_directories(gl3w PUBLIC "${{CMAKE_CURRENT_LIST_DIR}}/include")"#)?;
+        writeln!(out, "target_link_libraries(gl3w PUBLIC ${{CMAKE_DL_LIBS}})")?;
+    }
+    if exec.loader == Loader::Native {
+        // For the CFBundle functions resolving procs on macOS
+        let scope = if sources.is_empty() { "INTERFACE" } else { "PUBLIC" };
+        writeln!(out, "if(APPLE)\n  target_link_libraries(gl3w {} \"-framework CoreFoundation\")\nendif()", scope)?;
+    }
+    writeln!(out, "add_library(gl3w::gl3w ALIAS gl3w)")?;
+    Ok(())
+}
+
+/// Generate a compilation database with a single entry.
+fn gen_compile_commands<T: Write>(out: &mut T, directory: &Path, file: &Path, arguments: &[String]) -> io::Result<()>
+{
+    let arguments = arguments.iter().map(|a| json_string(a)).collect::<Vec<_>>();
+
+    writeln!(out, "[")?;
+    writeln!(out, "  {{")?;
+    writeln!(out, r#"    "directory": {},"#, json_string(&directory.display().to_string()))?;
+    writeln!(out, r#"    "file": {},"#, json_string(&file.display().to_string()))?;
+    writeln!(out, r#"    "arguments": [{}]"#, arguments.join(", "))?;
+    writeln!(out, "  }}")?;
+    writeln!(out, "]")?;
+    Ok(())
+}
+
+/// Generate a program which creates a context with the selected loader's
+/// library, then initializes gl3w and prints the OpenGL version.
+fn gen_smoke_test<T: Write>(out: &mut T, exec: &Gl3wExec) -> io::Result<()>
+{
+    write!(out, "{}", include_str!("template/gl3w.preamble.c"))?;
+    writeln!(out, "#include <stdio.h>")?;
+
+    // A single file needs its implementation pulled in somewhere
+    if let Gl3wPath::Single(_) = exec.path_gl3w {
+        if !exec.header_only {
+            writeln!(out, "#define GL3W_IMPLEMENTATION")?;
+        }
+    }
+
+    if exec.split_headers {
+        writeln!(out, "#include <GL/{}>\n", exec.internal_header_name())?;
+    }
+    else {
+        writeln!(out, "#include <GL/{}>\n", exec.header_name())?;
+    }
+    write!(out, "{}", exec.loader.smoke_template())?;
+    write!(out, "{}", include_str!("template/gl3w.smoke.main.c"))?;
+    Ok(())
+}
+
+/// Generate a combined gl3w.h from a list of procs.
+///
+/// This is based on gl3w-Single-File. Everything the implementation uses is
+/// declared before it, so the file compiles without including anything of
+/// its own: the typedefs, then the declarations, then the implementation.
+fn gen_gl3w_single<T: Write>(out: &mut T, exec: &Gl3wExec, glcorearb_h: &str, procs: &[Proc],
+                             version: Option<(u32, u32)>) -> io::Result<()>
+{
+    gen_gl3w_h(out, exec, glcorearb_h, procs, version)?;
+
+    if exec.header_only {
+        writeln!(out, r#"
+#ifndef GL3W_IMPLEMENTATION_DONE
+#define GL3W_IMPLEMENTATION_DONE
+"#
+        )?;
+    }
+    else {
+        writeln!(out, r#"
+#if defined(GL3W_IMPLEMENTATION) && !defined(GL3W_IMPLEMENTATION_DONE)
+#define GL3W_IMPLEMENTATION_DONE
+"#
+        )?;
+    }
+
+    gen_gl3w_c(out, exec, procs)?;
+
+    writeln!(out, r#"
+#endif /* GL3W_IMPLEMENTATION */
+"#
+    )?;
+
+    Ok(())
+}
+
+/// Parse the functions declared by a header read from `reader`, with the
+/// default options, for headers which do not come from a url or file, e.g.
+/// one embedded in the program or a decompressed stream.
+///
+/// ```
+/// use gl3w_native::procs_from_reader;
+///
+/// let header: &[u8] = b"\
+/// GLAPI void APIENTRY glViewport (GLint x, GLint y, GLsizei width, GLsizei height);
+/// GLAPI void APIENTRY glClear (GLbitfield mask);
+/// ";
+/// let procs = procs_from_reader(header).unwrap();
+/// let ids = procs.iter().map(|p| p.id.as_str()).collect::<Vec<_>>();
+/// assert_eq!(ids, ["glClear", "glViewport"]);
+/// assert_eq!(procs[0].var, "gl3wClear");
+/// ```
+pub fn procs_from_reader<R: Read>(mut reader: R) -> io::Result<Vec<Proc>> {
+    let mut header = String::new();
+    reader.read_to_string(&mut header)?;
+    Ok(Gl3wExec::default().gen_procs(&header))
+}
+
+/// Write gl3w.h and gl3w.c for `procs`, generated with the default options,
+/// to `header` and `source`. `Gl3wExec::generate_to_writers` takes options.
+///
+/// ```
+/// use gl3w_native::{generate_to_writers, Proc};
+///
+/// let (mut header, mut source) = (Vec::new(), Vec::new());
+/// generate_to_writers(&[Proc::new("glClear")], &mut header, &mut source).unwrap();
+///
+/// let header = String::from_utf8(header).unwrap();
+/// let source = String::from_utf8(source).unwrap();
+/// assert!(header.contains("extern PFNGLCLEARPROC"));
+/// assert!(header.contains("#define glClear"));
+/// assert!(source.contains(r#"gl3wClear = (PFNGLCLEARPROC) get_proc("glClear");"#));
+/// ```
+pub fn generate_to_writers(procs: &[Proc], header: &mut Write, source: &mut Write) -> io::Result<()> {
+    Gl3wExec::default().generate_to_writers(procs, header, source)
+}
+
+/// Write the single-header gl3w.h for `procs`, generated with the default
+/// options, to `out`. `Gl3wExec::generate_to_writer` takes options.
+///
+/// ```
+/// use gl3w_native::{generate_to_writer, Proc};
+///
+/// let mut out = Vec::new();
+/// generate_to_writer(&[Proc::new("glClear")], &mut out).unwrap();
+///
+/// let out = String::from_utf8(out).unwrap();
+/// assert!(out.contains("#if defined(GL3W_IMPLEMENTATION)"));
+/// assert!(out.contains("extern PFNGLCLEARPROC"));
+/// ```
+pub fn generate_to_writer(procs: &[Proc], out: &mut Write) -> io::Result<()> {
+    Gl3wExec::default().generate_to_writer(procs, out)
+}
+
+/// Fetch, parse and generate everything requested by `exec`, returning the
+/// stage which failed with its error on failure.
+pub fn run(exec: &Gl3wExec) -> Result<(), (&'static str, io::Error)> {
+    let start = time::precise_time_ns();
+
+    if exec.command == Command::PrintVersion {
+        println!("{}", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+
+    if exec.command == Command::DumpTemplates {
+        let dir = exec.template_dump_dir.as_ref().unwrap();
+        let dump = || -> io::Result<()> {
+            for &(name, contents) in TEMPLATES {
+                let path = dir.join(name);
+                create_parent_dir(&path)?;
+                File::create(&path)?.write_all(contents.as_bytes())?;
+            }
+            Ok(())
+        };
+        dump().map_err(|e| ("generate", e))?;
+        if !exec.quiet {
+            println!("wrote {} templates to {}", TEMPLATES.len(), dir.display());
+        }
+        return Ok(());
+    }
+
+    if exec.command == Command::ListOutputs {
+        for path in exec.output_paths() {
+            println!("{}", path.display());
+        }
+        return Ok(());
+    }
+
+    if exec.command == Command::CheckUrl || exec.command == Command::HeadValidate {
+        return exec.check_urls(exec.command == Command::CheckUrl).map_err(|e| ("fetch", e));
+    }
+
+    if exec.command == Command::LoaderOnly {
+        let (_, procs) = exec.existing_procs().map_err(|e| ("generate", e))?;
+        if let Some(observer) = exec.observer() {
+            observer.on_parsed(procs.len());
+        }
+        let path_c = match exec.path_gl3w {
+            Gl3wPath::Separate(_, ref path_c) => path_c,
+            Gl3wPath::Single(_) => unreachable!("rejected by parse_args")
+        };
+
+        let write = || -> io::Result<()> {
+            create_parent_dir(path_c)?;
+            File::create(path_c)?.write_all(exec.generate_source(&procs).as_bytes())
+        };
+        write().map_err(|e| ("generate", e))?;
+        if let Some(observer) = exec.observer() {
+            observer.on_file_written(path_c);
+        }
+        if !exec.quiet {
+            println!("generated {} functions: {}", procs.len(), path_c.display());
+        }
+        return Ok(());
+    }
+
+    let fetch_start = time::precise_time_ns();
+    let glcorearb_h = exec.get_glcorearb_h().map_err(|e| ("fetch", e))?;
+    exec.check_warnings().map_err(|e| ("fetch", e))?;
+    let fetch_ns = time::precise_time_ns() - fetch_start;
+
+    if exec.command == Command::FetchOnly {
+        if !exec.quiet {
+            println!("fetched {}", exec.source());
+        }
+        return Ok(());
+    }
+
+    if exec.command == Command::ListExtensions {
+        for extension in exec.gen_extensions(&glcorearb_h) {
+            println!("{}", extension);
+        }
+        return Ok(());
+    }
+
+    let digest = if exec.if_changed { Some(sha256::hex_digest(glcorearb_h.as_bytes())) } else { None };
+    if exec.command == Command::Generate && digest.as_ref().map_or(false, |d| exec.is_unchanged(d)) {
+        if !exec.quiet {
+            println!("{} unchanged, nothing generated", exec.source());
+        }
+        return Ok(());
+    }
+
+    // Should always succeed
+    let parse_start = time::precise_time_ns();
+    let (procs, filtered) = exec.gen_procs_filtered(&glcorearb_h);
+    let parse_ns = time::precise_time_ns() - parse_start;
+    if let Some(observer) = exec.observer() {
+        observer.on_parsed(procs.len());
+    }
+    let version = exec.detected_version(&glcorearb_h);
+    if exec.verbose {
+        match version {
+            Some((major, minor)) => eprintln!("detected OpenGL {}.{}", major, minor),
+            None => eprintln!("no GL_VERSION_x_y markers found")
+        }
+    }
+
+    if exec.runtime_info && !exec.has_runtime_info(&procs) {
+        warn(exec.quiet, &exec.warnings, "glGetString is not generated, skipping gl3wVersion");
+    }
+    exec.check_warnings().map_err(|e| ("generate", e))?;
+
+    // The smoke test prints the version string
+    if exec.path_smoke_test().is_some() && !procs.iter().any(|p| p.id == "glGetString") {
+        return Err(("generate", io::Error::new(io::ErrorKind::InvalidInput,
+            "--emit-smoke-test needs glGetString, which is not generated"
+        )));
+    }
+
+    // Caught before anything is written, e.g. when the header format changes
+    // and a pattern silently stops matching some declarations
+    if let Some(expected) = exec.expect_count {
+        if procs.len() != expected {
+            return Err(("verify", io::Error::new(io::ErrorKind::InvalidData, format!(
+                "{} functions found in {}, expected {}", procs.len(), exec.source(), expected
+            ))));
+        }
+    }
+
+    if exec.command == Command::ListProcs {
+        for p in &procs {
+            println!("{}", p.id);
+        }
+        return Ok(());
+    }
+
+    if exec.command == Command::Verify {
+        let stale = exec.check_current(&glcorearb_h, &procs, version).map_err(|e| ("verify", e))?;
+        if !stale.is_empty() {
+            return Err(("verify", io::Error::new(io::ErrorKind::InvalidData, format!(
+                "generated files are out of date:\n  {}", stale.join("\n  ")
+            ))));
+        }
+
+        if !exec.quiet {
+            println!("generated files are up to date");
+        }
+        return Ok(());
+    }
+
+    if exec.command == Command::DryDiff {
+        return exec.dry_diff(&glcorearb_h, &procs, version).map_err(|e| ("generate", e));
+    }
+
+    if exec.command == Command::Audit {
+        let (path, removed, added) = exec.audit(&procs).map_err(|e| ("verify", e))?;
+        if removed.is_empty() && added.is_empty() {
+            if !exec.quiet {
+                println!("{} declares the same functions as {}", path.display(), exec.source());
+            }
+            return Ok(());
+        }
+
+        if !removed.is_empty() {
+            println!("{}: {} functions no longer in {}:", path.display(), removed.len(), exec.source());
+            for id in &removed {
+                println!("  {}", id);
+            }
+        }
+        if !added.is_empty() {
+            println!("{}: {} functions missing:", path.display(), added.len());
+            for id in &added {
+                println!("  {}", id);
+            }
+        }
+        return Err(("verify", io::Error::new(io::ErrorKind::InvalidData, format!(
+            "{} has drifted from {}", path.display(), exec.source()
+        ))));
+    }
+
+    if exec.command == Command::AppendExtensions {
+        let (appended, written) = exec.append_extensions(&glcorearb_h, &procs).map_err(|e| ("generate", e))?;
+        if !exec.quiet {
+            if appended == 0 {
+                println!("nothing to append, the functions are already generated");
+            }
+            else {
+                let written = written.iter().map(|p| p.display().to_string()).collect::<Vec<_>>();
+                println!("appended {} functions: {}", appended, written.join(", "));
+            }
+        }
+        return Ok(());
+    }
+
+    let generate = || -> io::Result<Vec<PathBuf>> {
+        // The last manifest is about to be overwritten
+        let previous = match (exec.changelog.as_ref(), exec.path_manifest()) {
+            (Some(_), Some(path)) => read_manifest(path)?,
+            _ => None
+        };
+
+        let mut written = exec.gen(&glcorearb_h, &procs, version)?;
+        if let Some(ref dir) = exec.cmake_package {
+            let package = exec.gen_cmake_package(dir, &written, &glcorearb_h)?;
+            written.extend(package);
+        }
+        for target in &exec.outputs {
+            written.push(exec.gen_output(target, &glcorearb_h, &procs)?);
+        }
+
+        if let (Some(path), Some(previous)) = (exec.changelog.as_ref(), previous) {
+            if exec.gen_changelog(path, &previous, &procs)? {
+                written.push(path.clone());
+            }
+        }
+        if let Some(ref path) = exec.list_filtered {
+            create_parent_dir(path)?;
+            let mut f = File::create(path)?;
+            for id in &filtered {
+                writeln!(f, "{}", id)?;
+            }
+            written.push(path.clone());
+        }
+        Ok(written)
+    };
+    let generate_start = time::precise_time_ns();
+    let written = generate().map_err(|e| ("generate", e))?;
+    if exec.bench {
+        let generate_ns = time::precise_time_ns() - generate_start;
+        for &(stage, ns) in &[("fetch", fetch_ns), ("parse", parse_ns), ("generate", generate_ns)] {
+            eprintln!("{:<9} {:>10.3} ms", format!("{}:", stage), ns as f64 / 1e6);
+        }
+    }
+
+    if exec.verify_compile {
+        exec.verify(&glcorearb_h).map_err(|e| ("verify", e))?;
+        exec.check_warnings().map_err(|e| ("verify", e))?;
+    }
+
+    let mut written = written;
+    if let Some(ref path) = exec.stats {
+        let duration_ms = if exec.reproducible { 0 } else { (time::precise_time_ns() - start) / 1_000_000 };
+        let write_stats = || -> io::Result<()> {
+            create_parent_dir(path)?;
+            gen_stats_json(&mut File::create(path)?, exec, &glcorearb_h, &procs, version, duration_ms)
+        };
+        write_stats().map_err(|e| ("generate", e))?;
+        written.push(path.clone());
+    }
+
+    if let Some(ref path) = exec.depfile {
+        let write_depfile = || -> io::Result<()> {
+            create_parent_dir(path)?;
+            gen_depfile(&mut File::create(path)?, &written, &exec.input_paths())
+        };
+        write_depfile().map_err(|e| ("generate", e))?;
+        written.push(path.clone());
+    }
+
+    if let Some(digest) = digest {
+        let state = format!("{}  {}\n", digest, exec.source());
+        File::create(exec.path_state()).and_then(|mut f| f.write_all(state.as_bytes())).map_err(|e| ("generate", e))?;
+    }
+
+    if let Some(observer) = exec.observer() {
+        for path in &written {
+            observer.on_file_written(path);
+        }
+    }
+
+    let written = written.iter().map(|p| p.display().to_string()).collect::<Vec<_>>();
+    if !exec.quiet {
+        let summary = format!("generated {} functions: {}", procs.len(), written.join(", "));
+        // Keep stdout to the generated file
+        if exec.stdout.is_some() {
+            eprintln!("{}", summary);
+        }
+        else {
+            println!("{}", summary);
+        }
+    }
+    Ok(())
+}
+
+/// The 64-bit FNV-1a hash of `bytes`, which is stable across builds unlike
+/// the std hashers.
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Quote `s` as a C string literal. Anything but printable ASCII is
+/// written as a three digit octal escape, which a following digit cannot
+/// extend, and `?` is escaped so no trigraph is formed.
+fn c_string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for &b in s.as_bytes() {
+        match b {
+            b'"' | b'\\' | b'?' => {
+                quoted.push('\\');
+                quoted.push(b as char);
+            }
+            b if b < 0x20 || b > 0x7e => quoted.push_str(&format!("\\{:03o}", b)),
+            b => quoted.push(b as char)
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Quote `s` as a JSON string.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c)
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Report a failure during `stage` and exit.
+///
+/// With `json` set this is a single JSON object on stderr for tools wrapping
+/// us, otherwise the usual error message.
+fn fail(json: bool, stage: &str, kind: io::ErrorKind, message: &str) -> ! {
+    if json {
+        eprintln!(r#"{{"stage": {}, "kind": {}, "message": {}}}"#,
+                  json_string(stage), json_string(&format!("{:?}", kind)), json_string(message));
+    }
+    else {
+        println!("error: {}", message);
+    }
+    process::exit(1);
+}
+
+/// Replace each `@path` argument with the arguments in the file at `path`,
+/// as compiler toolchains do for long command lines.
+///
+/// Arguments are separated by whitespace, which double quotes keep
+/// together, and response files may name further response files.
+fn expand_response_files(args: Vec<String>, depth: usize) -> Result<Vec<String>, String> {
+    // Deep enough for any real use, reached quickly by a file naming itself
+    if depth > 16 {
+        return Err("response files nested too deeply".to_string());
+    }
+
+    let mut expanded = Vec::new();
+    for arg in args {
+        if !arg.starts_with('@') || arg.len() == 1 {
+            expanded.push(arg);
+            continue;
+        }
+
+        let path = &arg[1..];
+        let mut contents = String::new();
+        File::open(path).and_then(|mut f| f.read_to_string(&mut contents)).map_err(|e| {
+            format!("failed to read response file {}: {}", path, e)
+        })?;
+
+        // `""` is an empty argument, so a word is started by quotes too
+        let mut words = Vec::new();
+        let mut word = String::new();
+        let mut in_word = false;
+        let mut quoted = false;
+        for c in contents.chars() {
+            match c {
+                '"' => {
+                    quoted = !quoted;
+                    in_word = true;
+                }
+                c if c.is_whitespace() && !quoted => {
+                    if in_word {
+                        words.push(mem::replace(&mut word, String::new()));
+                        in_word = false;
+                    }
+                }
+                c => {
+                    word.push(c);
+                    in_word = true;
+                }
+            }
+        }
+        if quoted {
+            return Err(format!("unterminated quote in response file {}", path));
+        }
+        if in_word {
+            words.push(word);
+        }
+
+        expanded.extend(expand_response_files(words, depth + 1)?);
+    }
+    Ok(expanded)
+}
+
+/// Run the `gl3w-native` command line tool with `args` (excluding the
+/// program name), exiting the process on failure.
+pub fn run_cli<I: Iterator<Item = String>>(args: I) {
+    let args = args.collect::<Vec<_>>();
+    let is_json = |arg: &String| arg == "--json-errors" || arg == "--quiet-errors-json";
+
+    let args = match expand_response_files(args.clone(), 0) {
+        Ok(args) => args,
+        Err(e) => fail(args.iter().any(&is_json), "args", io::ErrorKind::InvalidInput, &e)
+    };
+    let json_errors = args.iter().any(&is_json);
+
+    let exec = match parse_args(args.into_iter()) {
+        Ok(exec) => exec,
+        Err(e) => fail(json_errors, "args", io::ErrorKind::InvalidInput, &e)
+    };
+
+    if let Err((stage, e)) = run(&exec) {
+        fail(json_errors, stage, e.kind(), &e.to_string());
+    }
+}
@@ -0,0 +1,20 @@
+#ifdef _WIN32
+static int has_context(void)
+{
+	return wglGetCurrentContext() != NULL;
+}
+#else
+static int has_context(void)
+{
+	void *(*get_current_context)(void);
+
+	if (!libgl)
+		return 0;
+
+	get_current_context = (void *(*)(void)) dlsym(libgl, "glXGetCurrentContext");
+	if (!get_current_context)
+		get_current_context = (void *(*)(void)) dlsym(libgl, "CGLGetCurrentContext");
+	return get_current_context && get_current_context() != NULL;
+}
+#endif
+
@@ -0,0 +1,6 @@
+#include <emscripten/html5.h>
+
+static int has_context(void)
+{
+	return emscripten_webgl_get_current_context() != 0;
+}
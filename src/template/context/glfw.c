@@ -0,0 +1,5 @@
+static int has_context(void)
+{
+	return glfwGetCurrentContext() != NULL;
+}
+
@@ -0,0 +1,7 @@
+static int has_context(void)
+{
+	void *(*get_current_context)(void);
+
+	get_current_context = (void *(*)(void)) dlsym(libgl, "CGLGetCurrentContext");
+	return get_current_context && get_current_context() != NULL;
+}
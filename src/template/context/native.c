@@ -0,0 +1,24 @@
+#ifdef _WIN32
+static int has_context(void)
+{
+	return wglGetCurrentContext() != NULL;
+}
+#elif defined(__APPLE__) || defined(__APPLE_CC__)
+static int has_context(void)
+{
+	void *(*get_current_context)(void);
+
+	get_current_context = (void *(*)(void)) CFBundleGetFunctionPointerForName(bundle,
+		CFSTR("CGLGetCurrentContext"));
+	return get_current_context && get_current_context() != NULL;
+}
+#else
+static int has_context(void)
+{
+	GLXContext (*get_current_context)(void);
+
+	get_current_context = (GLXContext (*)(void)) dlsym(libgl, "glXGetCurrentContext");
+	return get_current_context && get_current_context() != NULL;
+}
+#endif
+
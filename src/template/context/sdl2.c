@@ -0,0 +1,5 @@
+static int has_context(void)
+{
+	return SDL_GL_GetCurrentContext() != NULL;
+}
+
@@ -0,0 +1,5 @@
+#define GL3W_GROUP_CORE   0x1
+#define GL3W_GROUP_ARB    0x2
+#define GL3W_GROUP_VENDOR 0x4
+#define GL3W_GROUP_ALL    0x7
+int gl3wInitGroups(unsigned groups);
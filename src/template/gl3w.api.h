@@ -1,12 +1,3 @@
-#ifndef __gl3w_h_
-#define __gl3w_h_
-
-#include <GL/glcorearb.h>
-
-#ifndef __gl_h_
-#define __gl_h_
-#endif
-
 #ifdef __cplusplus
 extern "C" {
 #endif
@@ -17,5 +8,3 @@ typedef void (*GL3WglProc)(void);
 int gl3wInit(void);
 int gl3wIsSupported(int major, int minor);
 GL3WglProc gl3wGetProcAddress(const char *proc);
-
-/* OpenGL functions */
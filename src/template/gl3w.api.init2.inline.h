@@ -0,0 +1,2 @@
+typedef GL3WglProc (*GL3WGetProcAddressProc)(const char *proc);
+static inline int gl3wInit2(GL3WGetProcAddressProc proc);
@@ -0,0 +1,10 @@
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef void (*GL3WglProc)(void);
+
+/* gl3w api */
+static inline int gl3wInit(void);
+static inline int gl3wIsSupported(int major, int minor);
+static inline GL3WglProc gl3wGetProcAddress(const char *proc);
@@ -0,0 +1 @@
+int gl3wReinit(void);
@@ -0,0 +1 @@
+static inline int gl3wReinit(void);
@@ -0,0 +1,10 @@
+
+/* The context's version and identification, filled in by gl3wInit */
+struct gl3w_version {
+	int major, minor;
+	const char *vendor;
+	const char *renderer;
+	const char *version;
+};
+
+static struct gl3w_version gl3wVersion;
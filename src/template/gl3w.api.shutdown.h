@@ -0,0 +1 @@
+void gl3wShutdown(void);
@@ -0,0 +1 @@
+static inline void gl3wShutdown(void);
@@ -0,0 +1,6 @@
+
+#include <assert.h>
+
+/* Calling through an OpenGL name asserts its function pointer was loaded */
+#define GL3W_ASSERT_LOADED(proc, name) (assert((proc) && name " was not loaded"), (proc))
+
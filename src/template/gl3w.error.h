@@ -0,0 +1,9 @@
+/* Results of gl3wInit */
+enum gl3w_error {
+	GL3W_OK = 0,
+	GL3W_ERROR_INIT = -1,           /* glGetIntegerv could not be loaded */
+	GL3W_ERROR_LIBRARY_OPEN = -2,   /* the OpenGL library could not be opened */
+	GL3W_ERROR_OPENGL_VERSION = -3, /* the context is older than OpenGL 3.0 */
+	GL3W_ERROR_NO_CONTEXT = -4      /* no context is current, with --require-context */
+};
+
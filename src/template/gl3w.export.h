@@ -0,0 +1,18 @@
+
+/* Exported from a shared library built with GL3W_BUILD defined and
+ * imported otherwise. Define GL3W_API (empty for a static build) to
+ * override this */
+#ifndef GL3W_API
+#if defined(_WIN32) || defined(__CYGWIN__)
+#ifdef GL3W_BUILD
+#define GL3W_API __declspec(dllexport)
+#else
+#define GL3W_API __declspec(dllimport)
+#endif
+#elif defined(__GNUC__) && __GNUC__ >= 4
+#define GL3W_API __attribute__((visibility("default")))
+#else
+#define GL3W_API
+#endif
+#endif
+
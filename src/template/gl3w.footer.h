@@ -2,5 +2,3 @@
 }
 #endif
 
-#endif
-
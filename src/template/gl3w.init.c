@@ -0,0 +1,8 @@
+int gl3wInit(void)
+{
+	open_libgl();
+	load_procs();
+	close_libgl();
+	return parse_version();
+}
+
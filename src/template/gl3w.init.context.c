@@ -0,0 +1,12 @@
+int gl3wInit(void)
+{
+	open_libgl();
+	if (!has_context()) {
+		close_libgl();
+		return -2;
+	}
+	load_procs();
+	close_libgl();
+	return parse_version();
+}
+
@@ -0,0 +1,13 @@
+static void load_procs_from(GL3WGetProcAddressProc proc);
+
+static void load_procs(void)
+{
+	load_procs_from(get_proc);
+}
+
+int gl3wInit2(GL3WGetProcAddressProc proc)
+{
+	load_procs_from(proc);
+	return parse_version();
+}
+
@@ -0,0 +1,17 @@
+static unsigned char loaded_procs[(GL3W_PROC_COUNT + 7) / 8];
+
+static void set_loaded(int index, GL3WglProc proc)
+{
+	if (proc)
+		loaded_procs[index / 8] |= (unsigned char) (1 << index % 8);
+	else
+		loaded_procs[index / 8] &= (unsigned char) ~(1 << index % 8);
+}
+
+int gl3wProcLoaded(int index)
+{
+	if (index < 0 || index >= GL3W_PROC_COUNT)
+		return 0;
+	return loaded_procs[index / 8] >> index % 8 & 1;
+}
+
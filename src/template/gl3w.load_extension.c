@@ -0,0 +1,9 @@
+int gl3wLoadExtension(const char *ext)
+{
+	int res;
+
+	open_libgl();
+	res = load_extension_procs(ext);
+	close_libgl();
+	return res;
+}
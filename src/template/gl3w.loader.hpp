@@ -0,0 +1,26 @@
+#ifdef __cplusplus
+namespace gl3w {
+
+/* Loads the functions with gl3wInit for as long as it lives */
+class Loader {
+public:
+	Loader() : res(gl3wInit()) {}
+	~Loader()
+	{
+		if (res == 0)
+			gl3wShutdown();
+	}
+
+	int result() const { return res; }
+	bool ok() const { return res == 0; }
+
+private:
+	Loader(const Loader &);
+	Loader &operator=(const Loader &);
+
+	int res;
+};
+
+} /* namespace gl3w */
+#endif
+
@@ -0,0 +1,35 @@
+static void reset_procs(void);
+
+static int refcount;
+
+int gl3wInit(void)
+{
+	int res;
+
+	if (refcount > 0) {
+		refcount++;
+		return 0;
+	}
+
+	res = load();
+	if (res == 0)
+		refcount = 1;
+	return res;
+}
+
+int gl3wReinit(void)
+{
+	int res = load();
+
+	if (res == 0 && refcount == 0)
+		refcount = 1;
+	return res;
+}
+
+void gl3wShutdown(void)
+{
+	if (refcount == 0 || --refcount > 0)
+		return;
+	reset_procs();
+}
+
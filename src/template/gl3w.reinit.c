@@ -0,0 +1,17 @@
+static int loaded;
+
+int gl3wReinit(void)
+{
+	int res = load();
+
+	loaded = res == 0;
+	return res;
+}
+
+int gl3wInit(void)
+{
+	if (loaded)
+		return 0;
+	return gl3wReinit();
+}
+
@@ -0,0 +1,10 @@
+double gl3wSelfTest(void)
+{
+	int resolved = 0;
+	int i;
+
+	for (i = 0; i < GL3W_PROC_COUNT; i++)
+		resolved += gl3wProcLoaded(i);
+	return (double) resolved / GL3W_PROC_COUNT;
+}
+
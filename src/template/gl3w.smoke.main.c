@@ -0,0 +1,30 @@
+int main(int argc, char **argv)
+{
+	const GLubyte *version;
+	int ret = 1;
+
+	(void) argc;
+	(void) argv;
+
+	if (create_context()) {
+		fprintf(stderr, "failed to create an OpenGL context\n");
+		return 1;
+	}
+
+	if (gl3wInit()) {
+		fprintf(stderr, "failed to initialize gl3w\n");
+	}
+	else if (!glGetString) {
+		fprintf(stderr, "glGetString was not loaded\n");
+	}
+	else if (!(version = glGetString(GL_VERSION))) {
+		fprintf(stderr, "glGetString(GL_VERSION) failed\n");
+	}
+	else {
+		printf("OpenGL %s\n", (const char *) version);
+		ret = 0;
+	}
+
+	destroy_context();
+	return ret;
+}
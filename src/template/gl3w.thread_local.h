@@ -0,0 +1,8 @@
+
+#if defined(_MSC_VER)
+#define GL3W_THREAD_LOCAL __declspec(thread)
+#elif defined(__STDC_VERSION__) && __STDC_VERSION__ >= 201112L
+#define GL3W_THREAD_LOCAL _Thread_local
+#else
+#define GL3W_THREAD_LOCAL __thread
+#endif
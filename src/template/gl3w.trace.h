@@ -0,0 +1,8 @@
+
+/* Defining GL3W_TRACE logs each call through an OpenGL name, with
+ * GL3W_TRACE_CALL if defined or to stderr otherwise */
+#if defined(GL3W_TRACE) && !defined(GL3W_TRACE_CALL)
+#include <stdio.h>
+#define GL3W_TRACE_CALL(name) fprintf(stderr, "%s\n", name)
+#endif
+
@@ -0,0 +1,27 @@
+static struct {
+	int major, minor;
+} version;
+
+static int parse_version(void)
+{
+	if (!glGetIntegerv)
+		return -1;
+
+	glGetIntegerv(GL_MAJOR_VERSION, &version.major);
+	glGetIntegerv(GL_MINOR_VERSION, &version.minor);
+
+	gl3wVersion.major = version.major;
+	gl3wVersion.minor = version.minor;
+	if (glGetString) {
+		gl3wVersion.vendor = (const char *) glGetString(GL_VENDOR);
+		gl3wVersion.renderer = (const char *) glGetString(GL_RENDERER);
+		gl3wVersion.version = (const char *) glGetString(GL_VERSION);
+	}
+
+	if (version.major < 3)
+		return -1;
+	return 0;
+}
+
+static void load_procs(void);
+
@@ -0,0 +1,69 @@
+#ifndef GL3W_LIBGL_NAME
+#if defined(_WIN32)
+#define GL3W_LIBGL_NAME "opengl32.dll"
+#elif defined(__APPLE__) || defined(__APPLE_CC__)
+#define GL3W_LIBGL_NAME "/System/Library/Frameworks/OpenGL.framework/OpenGL"
+#else
+#define GL3W_LIBGL_NAME "libGL.so.1"
+#endif
+#endif
+
+#ifdef _WIN32
+#define WIN32_LEAN_AND_MEAN 1
+#include <windows.h>
+
+static HMODULE libgl;
+
+static void open_libgl(void)
+{
+	libgl = LoadLibraryA(GL3W_LIBGL_NAME);
+}
+
+static void close_libgl(void)
+{
+	if (libgl)
+		FreeLibrary(libgl);
+}
+
+static GL3WglProc get_proc(const char *proc)
+{
+	GL3WglProc res;
+
+	res = (GL3WglProc) wglGetProcAddress(proc);
+	if (!res && libgl)
+		res = (GL3WglProc) GetProcAddress(libgl, proc);
+	return res;
+}
+#else
+#include <dlfcn.h>
+#include <stddef.h>
+
+typedef GL3WglProc (*GL3WGetProcAddressARB)(const unsigned char *proc);
+
+static void *libgl;
+static GL3WGetProcAddressARB get_proc_address;
+
+static void open_libgl(void)
+{
+	libgl = dlopen(GL3W_LIBGL_NAME, RTLD_LAZY | RTLD_GLOBAL);
+	if (libgl)
+		get_proc_address = (GL3WGetProcAddressARB) dlsym(libgl, "glXGetProcAddressARB");
+}
+
+static void close_libgl(void)
+{
+	if (libgl)
+		dlclose(libgl);
+}
+
+static GL3WglProc get_proc(const char *proc)
+{
+	GL3WglProc res = NULL;
+
+	if (get_proc_address)
+		res = get_proc_address((const unsigned char *) proc);
+	if (!res && libgl)
+		res = (GL3WglProc) dlsym(libgl, proc);
+	return res;
+}
+#endif
@@ -0,0 +1,15 @@
+#include <emscripten.h>
+
+static void open_libgl(void)
+{
+}
+
+static void close_libgl(void)
+{
+}
+
+/* Functions WebGL has no equivalent for resolve to NULL */
+static GL3WglProc get_proc(const char *proc)
+{
+	return (GL3WglProc) emscripten_GetProcAddress(proc);
+}
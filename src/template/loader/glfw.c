@@ -0,0 +1,15 @@
+#define GLFW_INCLUDE_NONE
+#include <GLFW/glfw3.h>
+
+static void open_libgl(void)
+{
+}
+
+static void close_libgl(void)
+{
+}
+
+static GL3WglProc get_proc(const char *proc)
+{
+	return (GL3WglProc) glfwGetProcAddress(proc);
+}
@@ -0,0 +1,28 @@
+#if !defined(__APPLE__) && !defined(__APPLE_CC__)
+#error "the macos loader only supports macOS"
+#endif
+
+#include <dlfcn.h>
+#include <stddef.h>
+
+static void *libgl;
+
+static void open_libgl(void)
+{
+	libgl = dlopen("/System/Library/Frameworks/OpenGL.framework/OpenGL", RTLD_LAZY | RTLD_LOCAL);
+}
+
+static void close_libgl(void)
+{
+	if (libgl)
+		dlclose(libgl);
+}
+
+/* The framework exports every function it implements, so unlike WGL and GLX
+ * there is no GetProcAddress to ask first. */
+static GL3WglProc get_proc(const char *proc)
+{
+	if (!libgl)
+		return NULL;
+	return (GL3WglProc) dlsym(libgl, proc);
+}
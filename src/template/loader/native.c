@@ -1,5 +1,3 @@
-#include <GL/gl3w.h>
-
 #ifdef _WIN32
 #define WIN32_LEAN_AND_MEAN 1
 #include <windows.h>
@@ -28,8 +26,8 @@ static GL3WglProc get_proc(const char *proc)
 #elif defined(__APPLE__) || defined(__APPLE_CC__)
 #include <Carbon/Carbon.h>
 
-CFBundleRef bundle;
-CFURLRef bundleURL;
+static CFBundleRef bundle;
+static CFURLRef bundleURL;
 
 static void open_libgl(void)
 {
@@ -85,45 +83,3 @@ static GL3WglProc get_proc(const char *proc)
 	return res;
 }
 #endif
-
-static struct {
-	int major, minor;
-} version;
-
-static int parse_version(void)
-{
-	if (!glGetIntegerv)
-		return -1;
-
-	glGetIntegerv(GL_MAJOR_VERSION, &version.major);
-	glGetIntegerv(GL_MINOR_VERSION, &version.minor);
-
-	if (version.major < 3)
-		return -1;
-	return 0;
-}
-
-static void load_procs(void);
-
-int gl3wInit(void)
-{
-	open_libgl();
-	load_procs();
-	close_libgl();
-	return parse_version();
-}
-
-int gl3wIsSupported(int major, int minor)
-{
-	if (major < 3)
-		return 0;
-	if (version.major == major)
-		return version.minor >= minor;
-	return version.major >= major;
-}
-
-GL3WglProc gl3wGetProcAddress(const char *proc)
-{
-	return get_proc(proc);
-}
-
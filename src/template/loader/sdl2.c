@@ -0,0 +1,14 @@
+#include <SDL2/SDL.h>
+
+static void open_libgl(void)
+{
+}
+
+static void close_libgl(void)
+{
+}
+
+static GL3WglProc get_proc(const char *proc)
+{
+	return (GL3WglProc) SDL_GL_GetProcAddress(proc);
+}
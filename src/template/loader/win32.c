@@ -0,0 +1,31 @@
+#ifndef _WIN32
+#error "the win32 loader only supports Windows"
+#endif
+
+#define WIN32_LEAN_AND_MEAN 1
+#include <windows.h>
+
+static HMODULE libgl;
+
+static void open_libgl(void)
+{
+	libgl = LoadLibraryA("opengl32.dll");
+}
+
+static void close_libgl(void)
+{
+	FreeLibrary(libgl);
+}
+
+/* wglGetProcAddress only resolves functions past OpenGL 1.1, the rest are
+ * exported by opengl32.dll. Some drivers return 1, 2, 3 or -1 instead of
+ * NULL for functions they do not provide. */
+static GL3WglProc get_proc(const char *proc)
+{
+	PROC res;
+
+	res = wglGetProcAddress(proc);
+	if (res == NULL || res == (PROC) 1 || res == (PROC) 2 || res == (PROC) 3 || res == (PROC) -1)
+		return (GL3WglProc) GetProcAddress(libgl, proc);
+	return (GL3WglProc) res;
+}
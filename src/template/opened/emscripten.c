@@ -0,0 +1,5 @@
+/* The browser provides WebGL, there is no library to open */
+static int libgl_opened(void)
+{
+	return 1;
+}
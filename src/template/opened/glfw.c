@@ -0,0 +1,6 @@
+/* GLFW opens the library itself */
+static int libgl_opened(void)
+{
+	return 1;
+}
+
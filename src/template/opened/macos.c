@@ -0,0 +1,5 @@
+static int libgl_opened(void)
+{
+	return libgl != NULL;
+}
+
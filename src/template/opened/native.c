@@ -0,0 +1,12 @@
+#if defined(__APPLE__) || defined(__APPLE_CC__)
+static int libgl_opened(void)
+{
+	return bundle != NULL;
+}
+#else
+static int libgl_opened(void)
+{
+	return libgl != NULL;
+}
+#endif
+
@@ -0,0 +1,6 @@
+/* SDL opens the library itself */
+static int libgl_opened(void)
+{
+	return 1;
+}
+
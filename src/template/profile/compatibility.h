@@ -0,0 +1,3 @@
+#include <GL/gl.h>
+#include <GL/glext.h>
+
@@ -0,0 +1,12 @@
+#if defined(_WIN32)
+#ifndef WIN32_LEAN_AND_MEAN
+#define WIN32_LEAN_AND_MEAN 1
+#endif
+#include <windows.h>
+#endif
+#include <GL/glcorearb.h>
+
+#ifndef __gl_h_
+#define __gl_h_
+#endif
+
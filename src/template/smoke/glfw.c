@@ -0,0 +1,30 @@
+#define GLFW_INCLUDE_NONE
+#include <GLFW/glfw3.h>
+
+static GLFWwindow *window;
+
+static int create_context(void)
+{
+	if (!glfwInit())
+		return -1;
+
+	glfwWindowHint(GLFW_CONTEXT_VERSION_MAJOR, 3);
+	glfwWindowHint(GLFW_CONTEXT_VERSION_MINOR, 2);
+	glfwWindowHint(GLFW_OPENGL_PROFILE, GLFW_OPENGL_CORE_PROFILE);
+	glfwWindowHint(GLFW_OPENGL_FORWARD_COMPAT, GLFW_TRUE);
+	glfwWindowHint(GLFW_VISIBLE, GLFW_FALSE);
+
+	window = glfwCreateWindow(64, 64, "gl3w", NULL, NULL);
+	if (!window)
+		return -1;
+
+	glfwMakeContextCurrent(window);
+	return 0;
+}
+
+static void destroy_context(void)
+{
+	glfwDestroyWindow(window);
+	glfwTerminate();
+}
+
@@ -0,0 +1,110 @@
+#ifdef _WIN32
+#define WIN32_LEAN_AND_MEAN 1
+#include <windows.h>
+
+static HWND window;
+static HDC dc;
+static HGLRC context;
+
+static int create_context(void)
+{
+	PIXELFORMATDESCRIPTOR pfd = {
+		sizeof(pfd), 1, PFD_DRAW_TO_WINDOW | PFD_SUPPORT_OPENGL | PFD_DOUBLEBUFFER,
+		PFD_TYPE_RGBA, 32
+	};
+
+	window = CreateWindowA("STATIC", "gl3w", WS_POPUP, 0, 0, 64, 64,
+		NULL, NULL, GetModuleHandleA(NULL), NULL);
+	if (!window)
+		return -1;
+
+	dc = GetDC(window);
+	if (!SetPixelFormat(dc, ChoosePixelFormat(dc, &pfd), &pfd))
+		return -1;
+
+	context = wglCreateContext(dc);
+	if (!context)
+		return -1;
+	return wglMakeCurrent(dc, context) ? 0 : -1;
+}
+
+static void destroy_context(void)
+{
+	wglMakeCurrent(NULL, NULL);
+	wglDeleteContext(context);
+	ReleaseDC(window, dc);
+	DestroyWindow(window);
+}
+#elif defined(__APPLE__) || defined(__APPLE_CC__)
+#include <OpenGL/OpenGL.h>
+
+static CGLContextObj context;
+
+static int create_context(void)
+{
+	CGLPixelFormatAttribute attribs[] = {
+		kCGLPFAOpenGLProfile, (CGLPixelFormatAttribute) kCGLOGLPVersion_3_2_Core,
+		(CGLPixelFormatAttribute) 0
+	};
+	CGLPixelFormatObj format;
+	GLint count;
+
+	if (CGLChoosePixelFormat(attribs, &format, &count) != kCGLNoError || !format)
+		return -1;
+
+	CGLCreateContext(format, NULL, &context);
+	CGLDestroyPixelFormat(format);
+	if (!context)
+		return -1;
+	return CGLSetCurrentContext(context) == kCGLNoError ? 0 : -1;
+}
+
+static void destroy_context(void)
+{
+	CGLSetCurrentContext(NULL);
+	CGLDestroyContext(context);
+}
+#else
+#include <X11/Xlib.h>
+#include <GL/glx.h>
+
+static Display *display;
+static Window window;
+static GLXContext context;
+
+static int create_context(void)
+{
+	int attribs[] = { GLX_RGBA, GLX_DOUBLEBUFFER, None };
+	XSetWindowAttributes swa;
+	XVisualInfo *vi;
+
+	display = XOpenDisplay(NULL);
+	if (!display)
+		return -1;
+
+	vi = glXChooseVisual(display, DefaultScreen(display), attribs);
+	if (!vi)
+		return -1;
+
+	swa.colormap = XCreateColormap(display, RootWindow(display, vi->screen),
+		vi->visual, AllocNone);
+	swa.border_pixel = 0;
+	window = XCreateWindow(display, RootWindow(display, vi->screen), 0, 0, 64, 64,
+		0, vi->depth, InputOutput, vi->visual, CWBorderPixel | CWColormap, &swa);
+
+	context = glXCreateContext(display, vi, NULL, True);
+	XFree(vi);
+	if (!context)
+		return -1;
+	return glXMakeCurrent(display, window, context) ? 0 : -1;
+}
+
+static void destroy_context(void)
+{
+	glXMakeCurrent(display, None, NULL);
+	glXDestroyContext(display, context);
+	XDestroyWindow(display, window);
+	XCloseDisplay(display);
+}
+#endif
+
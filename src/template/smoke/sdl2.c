@@ -0,0 +1,29 @@
+#include <SDL2/SDL.h>
+
+static SDL_Window *window;
+static SDL_GLContext context;
+
+static int create_context(void)
+{
+	if (SDL_Init(SDL_INIT_VIDEO) != 0)
+		return -1;
+
+	SDL_GL_SetAttribute(SDL_GL_CONTEXT_MAJOR_VERSION, 3);
+	SDL_GL_SetAttribute(SDL_GL_CONTEXT_MINOR_VERSION, 2);
+	SDL_GL_SetAttribute(SDL_GL_CONTEXT_PROFILE_MASK, SDL_GL_CONTEXT_PROFILE_CORE);
+
+	window = SDL_CreateWindow("gl3w", 0, 0, 64, 64, SDL_WINDOW_OPENGL | SDL_WINDOW_HIDDEN);
+	if (!window)
+		return -1;
+
+	context = SDL_GL_CreateContext(window);
+	return context ? 0 : -1;
+}
+
+static void destroy_context(void)
+{
+	SDL_GL_DeleteContext(context);
+	SDL_DestroyWindow(window);
+	SDL_Quit();
+}
+
@@ -0,0 +1,111 @@
+/* Generated by gl3w-native x.y.z
+ * source: https://registry.khronos.org/OpenGL/api/GL/glcorearb.h
+ * procs: 20
+ */
+/*
+
+    This file was generated with gl3w-native, a port of gl3w
+    (hosted at https://github.com/tiehuis/glew-native)
+    (see also https://github.com/skaslev/gl3w)
+
+    This is free and unencumbered software released into the public domain.
+
+    Anyone is free to copy, modify, publish, use, compile, sell, or
+    distribute this software, either in source code form or as a compiled
+    binary, for any purpose, commercial or non-commercial, and by any
+    means.
+
+    In jurisdictions that recognize copyright laws, the author or authors
+    of this software dedicate any and all copyright interest in the
+    software to the public domain. We make this dedication for the benefit
+    of the public at large and to the detriment of our heirs and
+    successors. We intend this dedication to be an overt act of
+    relinquishment in perpetuity of all present and future rights to this
+    software under copyright law.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+    EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+    MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.
+    IN NO EVENT SHALL THE AUTHORS BE LIABLE FOR ANY CLAIM, DAMAGES OR
+    OTHER LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE,
+    ARISING FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR
+    OTHER DEALINGS IN THE SOFTWARE.
+
+*/
+
+#ifndef __gl3w_h_
+#define __gl3w_h_
+
+#include <GL/glcorearb.h>
+
+#ifndef __gl_h_
+#define __gl_h_
+#endif
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef void (*GL3WglProc)(void);
+
+/* gl3w api */
+int gl3wInit(void);
+int gl3wIsSupported(int major, int minor);
+GL3WglProc gl3wGetProcAddress(const char *proc);
+
+/* OpenGL functions */
+extern PFNGLCLEARPROC gl3wClear;
+extern PFNGLCLEARCOLORPROC gl3wClearColor;
+extern PFNGLGETSTRINGPROC gl3wGetString;
+extern PFNGLGETINTEGERVPROC gl3wGetIntegerv;
+extern PFNGLVIEWPORTPROC gl3wViewport;
+extern PFNGLBINDBUFFERPROC gl3wBindBuffer;
+extern PFNGLGENBUFFERSPROC gl3wGenBuffers;
+extern PFNGLBUFFERDATAPROC gl3wBufferData;
+extern PFNGLBUFFERSUBDATAPROC gl3wBufferSubData;
+extern PFNGLGETSTRINGIPROC gl3wGetStringi;
+extern PFNGLBINDVERTEXARRAYPROC gl3wBindVertexArray;
+extern PFNGLFENCESYNCPROC gl3wFenceSync;
+extern PFNGLCLIENTWAITSYNCPROC gl3wClientWaitSync;
+extern PFNGLVERTEXATTRIBDIVISORPROC gl3wVertexAttribDivisor;
+extern PFNGLMINSAMPLESHADINGPROC gl3wMinSampleShading;
+extern PFNGLCLIPCONTROLPROC gl3wClipControl;
+extern PFNGLCREATEBUFFERSPROC gl3wCreateBuffers;
+extern PFNGLDEBUGMESSAGECONTROLARBPROC gl3wDebugMessageControlARB;
+extern PFNGLDEBUGMESSAGECALLBACKARBPROC gl3wDebugMessageCallbackARB;
+extern PFNGLTEXTUREBARRIERNVPROC gl3wTextureBarrierNV;
+
+#define glClear gl3wClear
+#define glClearColor gl3wClearColor
+#define glGetString gl3wGetString
+#define glGetIntegerv gl3wGetIntegerv
+#define glViewport gl3wViewport
+#define glBindBuffer gl3wBindBuffer
+#define glGenBuffers gl3wGenBuffers
+#define glBufferData gl3wBufferData
+#define glBufferSubData gl3wBufferSubData
+#define glGetStringi gl3wGetStringi
+#define glBindVertexArray gl3wBindVertexArray
+#define glFenceSync gl3wFenceSync
+#define glClientWaitSync gl3wClientWaitSync
+#define glVertexAttribDivisor gl3wVertexAttribDivisor
+#define glMinSampleShading gl3wMinSampleShading
+#define glClipControl gl3wClipControl
+#define glCreateBuffers gl3wCreateBuffers
+#define glDebugMessageControlARB gl3wDebugMessageControlARB
+#define glDebugMessageCallbackARB gl3wDebugMessageCallbackARB
+#define glTextureBarrierNV gl3wTextureBarrierNV
+
+/* Version of gl3w-native which generated this file */
+#define GL3W_NATIVE_GENERATOR_VERSION "x.y.z"
+
+/* Highest OpenGL version declared by glcorearb.h */
+#define GL3W_GL_VERSION_MAJOR 4
+#define GL3W_GL_VERSION_MINOR 5
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
+
@@ -0,0 +1,2380 @@
+//! Tests generating from a cut down glcorearb.h. Nothing is downloaded,
+//! headers which would be are served by `serve` instead.
+//!
+//! Tests run in parallel, so each works in a directory of its own rather
+//! than the current one.
+
+use super::*;
+use std::net::TcpListener;
+
+/// The fixture, in the layout of the real glcorearb.h.
+const GLCOREARB_H: &str = include_str!("glcorearb.h");
+
+/// A vendor header to combine with the fixture.
+const VENDOR_H: &str = "\
+#ifndef GL_EXT_vendor_thing
+#define GL_EXT_vendor_thing 1
+typedef void (APIENTRYP PFNGLVENDORTHINGEXTPROC) (GLuint thing);
+#ifdef GL_GLEXT_PROTOTYPES
+GLAPI void APIENTRY glVendorThingEXT (GLuint thing);
+#endif
+#endif /* GL_EXT_vendor_thing */
+";
+
+/// Parse `args` as given on the command line.
+fn exec(args: &[&str]) -> Gl3wExec {
+    parse_args(args.iter().map(|arg| arg.to_string())).unwrap()
+}
+
+/// The error parsing `args` gives.
+fn exec_err(args: &[&str]) -> String {
+    parse_args(args.iter().map(|arg| arg.to_string())).unwrap_err()
+}
+
+/// `exec`, with the cache and generated files moved into `dir`.
+fn exec_in(dir: &Path, args: &[&str]) -> Gl3wExec {
+    let mut exec = exec(args);
+    exec.path_glcorearb = dir.join(&exec.path_glcorearb);
+    exec.path_glext = dir.join(&exec.path_glext);
+    let path_gl3w = match exec.path_gl3w {
+        Gl3wPath::Single(ref path) => Gl3wPath::Single(dir.join(path)),
+        Gl3wPath::Separate(ref path_h, ref path_c) => Gl3wPath::Separate(dir.join(path_h), dir.join(path_c))
+    };
+    exec.path_gl3w = path_gl3w;
+    exec
+}
+
+/// A new empty directory for the test `name`.
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("gl3w-native-test-{}", name));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Cache `header` where `exec` would download its header to.
+fn cache(exec: &Gl3wExec, header: &str) {
+    let path = if exec.profile == Profile::Compatibility { &exec.path_glext } else { &exec.path_glcorearb };
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    write_header(path, header).unwrap();
+}
+
+/// Write `contents` to `name` in `dir`, returning the path as an argument.
+fn fixture(dir: &Path, name: &str, contents: &str) -> String {
+    let path = dir.join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    File::create(&path).unwrap().write_all(contents.as_bytes()).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+/// Generate from the fixture into `dir` with `args`.
+fn generate(dir: &Path, args: &[&str]) -> Gl3wExec {
+    let exec = exec_in(dir, args);
+    cache(&exec, GLCOREARB_H);
+    run(&exec).unwrap();
+    exec
+}
+
+/// The gl3w.h generated from the fixture with `args`, without writing it.
+fn header_with(args: &[&str]) -> String {
+    let exec = exec(args);
+    exec.generate_header(GLCOREARB_H, &exec.gen_procs(GLCOREARB_H), exec.detected_version(GLCOREARB_H))
+}
+
+/// The gl3w.c generated from the fixture with `args`, without writing it.
+fn source_with(args: &[&str]) -> String {
+    let exec = exec(args);
+    exec.generate_source(&exec.gen_procs(GLCOREARB_H))
+}
+
+/// The names of the functions `exec` generates from the fixture.
+fn ids(exec: &Gl3wExec) -> Vec<String> {
+    exec.gen_procs(GLCOREARB_H).into_iter().map(|p| p.id).collect()
+}
+
+/// The generated header and source of `exec`, the source being empty for a
+/// single file.
+fn generated(exec: &Gl3wExec) -> (String, String) {
+    match exec.path_gl3w {
+        Gl3wPath::Single(ref path) => (read(path), String::new()),
+        Gl3wPath::Separate(ref path_h, ref path_c) => (read(path_h), read(path_c))
+    }
+}
+
+/// The words of the first line of `text` containing `needle`.
+fn line_with<'a>(text: &'a str, needle: &str) -> Vec<&'a str> {
+    text.lines().find(|l| l.contains(needle)).unwrap().split_whitespace().collect()
+}
+
+fn read<P: AsRef<Path>>(path: P) -> String {
+    let mut contents = String::new();
+    File::open(path).and_then(|mut f| f.read_to_string(&mut contents)).unwrap();
+    contents
+}
+
+/// Run `f`, returning the warnings it gave on this thread.
+fn warnings<F: FnOnce()>(f: F) -> Vec<String> {
+    WARNED.with(|warned| warned.borrow_mut().clear());
+    f();
+    WARNED.with(|warned| warned.borrow_mut().split_off(0))
+}
+
+/// Answer each request on a local port with the first of `responses` left
+/// for its path, until none are left. Returns the url of the server and
+/// each request received, up to the end of its headers.
+fn serve(responses: Vec<(&str, String)>) -> (String, mpsc::Receiver<String>) {
+    let mut responses = responses.into_iter().map(|(path, response)| (path.to_string(), response)).collect::<Vec<_>>();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        while !responses.is_empty() {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut byte = [0];
+            while !request.ends_with(b"\r\n\r\n") && stream.read(&mut byte).unwrap() == 1 {
+                request.push(byte[0]);
+            }
+            let request = String::from_utf8_lossy(&request).into_owned();
+
+            let path = request.split_whitespace().nth(1).unwrap_or("").to_string();
+            let i = responses.iter().position(|&(ref p, _)| *p == path).expect("unexpected request");
+            stream.write_all(responses.remove(i).1.as_bytes()).unwrap();
+            let _ = tx.send(request);
+        }
+    });
+    (url, rx)
+}
+
+/// An HTTP response with `status`, the extra `headers` and `body`.
+fn response(status: &str, headers: &[&str], body: &str) -> String {
+    let mut response = format!("HTTP/1.1 {}\r\nConnection: close\r\nContent-Length: {}\r\n", status, body.len());
+    for header in headers {
+        response.push_str(header);
+        response.push_str("\r\n");
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+    response
+}
+
+/// Run only the test `name` again in a child process in `dir`, with
+/// `GL3W_NATIVE_TEST_CHILD` set so it can tell it is the child.
+fn run_child(name: &str, dir: &Path) -> process::Output {
+    process::Command::new(env::current_exe().unwrap())
+        .args(&[&format!("tests::{}", name), "--exact", "--nocapture"])
+        .env("GL3W_NATIVE_TEST_CHILD", "1")
+        .current_dir(dir)
+        .output().unwrap()
+}
+
+/// Whether there is a C compiler to verify with, checked in `dir`.
+fn has_compiler(dir: &Path) -> bool {
+    let path = dir.join("empty.c");
+    File::create(&path).unwrap().write_all(b"int main(void) { return 0; }\n").unwrap();
+    compile::Compiler::discover(compile::Lang::C).check(&path, &[], &[]).unwrap_or(false)
+}
+
+#[test]
+fn verify_compiles_fixture() {
+    // Without a compiler this only warns
+    let dir = temp_dir("verify");
+    let exec = generate(&dir, &[]);
+    exec.verify(GLCOREARB_H).unwrap();
+}
+
+#[test]
+fn verify_fails_broken_source() {
+    let dir = temp_dir("verify-broken");
+    let exec = generate(&dir, &[]);
+    if let Gl3wPath::Separate(_, ref path_c) = exec.path_gl3w {
+        File::create(path_c).unwrap().write_all(b"int broken(void) { return }\n").unwrap();
+    }
+    if has_compiler(&dir) {
+        assert!(exec.verify(GLCOREARB_H).is_err());
+    }
+}
+
+#[test]
+fn enums_skip_markers() {
+    let enums = Gl3wExec::default().gen_enums(GLCOREARB_H, &[]);
+    let names = enums.iter().map(|e| e.0.as_str()).collect::<Vec<_>>();
+    assert!(names.contains(&"GL_COLOR_BUFFER_BIT"));
+    assert!(names.contains(&"GL_DEBUG_OUTPUT_SYNCHRONOUS_ARB"));
+    assert!(!names.contains(&"GL_VERSION_1_0"));
+    assert!(!names.contains(&"GL_ARB_debug_output"));
+}
+
+#[test]
+fn repeated_runs_are_identical() {
+    let dir = temp_dir("repeated");
+    let exec = generate(&dir, &["--no-timestamp"]);
+    let first = generated(&exec);
+    run(&exec).unwrap();
+    assert_eq!(generated(&exec), first);
+}
+
+#[test]
+fn typedefs_follow_prototypes() {
+    let exec = Gl3wExec::default();
+    let procs = exec.gen_procs(GLCOREARB_H).into_iter().filter(|p| p.id == "glFenceSync").collect::<Vec<_>>();
+    let typedefs = exec.gen_typedefs(GLCOREARB_H, &procs);
+    let names = typedefs.iter().map(|t| t.0.as_str()).collect::<Vec<_>>();
+    assert!(names.contains(&"GLsync"));
+    assert!(names.contains(&"GLbitfield"));
+    assert!(!names.contains(&"GLuint64"));
+}
+
+#[test]
+fn compressed_cache_round_trips() {
+    let dir = temp_dir("compress-cache");
+    let exec = exec_in(&dir, &["--compress-cache"]);
+    assert!(is_gzip(&exec.path_glcorearb));
+    cache(&exec, GLCOREARB_H);
+    let cached = exec.get_glcorearb_h().unwrap();
+    assert_eq!(exec.gen_procs(&cached), exec.gen_procs(GLCOREARB_H));
+}
+
+#[test]
+fn metadata_records_source_and_count() {
+    let procs = vec![Proc::new("glClear"), Proc::new("glViewport")];
+    let mut exec = Gl3wExec::default();
+    let metadata = |exec: &Gl3wExec| {
+        let mut out = Vec::new();
+        exec.gen_metadata(&mut out, &procs).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    assert!(metadata(&exec).contains(&format!(" * source: {}\n", exec.url_glcorearb)));
+    assert!(metadata(&exec).contains(" * procs: 2\n"));
+    assert!(metadata(&exec).contains(" * date: "));
+
+    exec.no_timestamp = true;
+    assert!(!metadata(&exec).contains("date"));
+}
+
+#[test]
+fn inputs_are_combined() {
+    let dir = temp_dir("inputs");
+    let core = fixture(&dir, "glcorearb.h", GLCOREARB_H);
+    let vendor = fixture(&dir, "vendor.h", VENDOR_H);
+    let exec = exec_in(&dir, &["--input", &core, "--input", &vendor]);
+    let ids = exec.gen_procs(&exec.get_glcorearb_h().unwrap()).into_iter().map(|p| p.id).collect::<Vec<_>>();
+    assert!(ids.contains(&"glClear".to_string()));
+    assert!(ids.contains(&"glVendorThingEXT".to_string()));
+    assert_eq!(ids.len(), exec.gen_procs(GLCOREARB_H).len() + 1);
+}
+
+#[test]
+fn empty_header_fails() {
+    let dir = temp_dir("empty");
+    let exec = exec_in(&dir, &[]);
+    cache(&exec, "#ifndef __gl_glcorearb_h_\n#define __gl_glcorearb_h_ 1\n#endif\n");
+    let procs = exec.gen_procs(&exec.get_glcorearb_h().unwrap());
+    let e = exec.gen(GLCOREARB_H, &procs, None).unwrap_err();
+    assert!(e.to_string().starts_with("no OpenGL functions found in "));
+    assert!(!dir.join("src/gl3w.h").exists());
+
+    let exec = exec_in(&dir, &["--allow-empty"]);
+    exec.gen(GLCOREARB_H, &procs, None).unwrap();
+    assert!(dir.join("src/gl3w.h").exists());
+}
+
+#[test]
+fn sdl2_loader() {
+    let source = source_with(&["--loader", "sdl2"]);
+    assert!(source.contains("#include <SDL2/SDL.h>"));
+    assert!(source.contains("SDL_GL_GetProcAddress(proc)"));
+    assert!(!source.contains("dlopen"));
+}
+
+#[test]
+fn glfw_loader() {
+    let source = source_with(&["--loader", "glfw"]);
+    assert!(source.contains("#include <GLFW/glfw3.h>"));
+    assert!(source.contains("glfwGetProcAddress(proc)"));
+}
+
+#[test]
+fn default_url_is_current_registry() {
+    assert_eq!(Gl3wExec::default().url_glcorearb, "https://registry.khronos.org/OpenGL/api/GL/glcorearb.h");
+}
+
+#[test]
+fn old_url_falls_back_to_current() {
+    // Both requests fail without touching the network, the second naming
+    // the url retried
+    let client = Http::new(Vec::new(), false, true);
+    let path = temp_dir("old-url").join("glcorearb.h");
+    let fetch = Fetch { quiet: true, ..Fetch::default() };
+    let e = fetch_cached(&client, URL_GLCOREARB_OLD, &path, fetch, &AtomicUsize::new(0), None).unwrap_err();
+    assert!(e.to_string().starts_with(&format!("failed to fetch {}: ", URL_GLCOREARB)));
+}
+
+#[test]
+fn typedef_only_functions_are_found() {
+    // The name is only spelled out in a comment, and the extension name
+    // must not be taken for the start of the typedef
+    let header = "\
+#ifndef GL_EXT_typedef_only
+#define GL_EXT_typedef_only 1
+/* glTypedefOnlyEXT has no prototype */
+typedef void (APIENTRYP PFNGLTYPEDEFONLYEXTPROC) (GLuint value);
+typedef void (APIENTRYP PFNGLUNNAMEDEXTPROC) (void);
+#endif /* GL_EXT_typedef_only */
+";
+    let procs = Gl3wExec::default().gen_procs(&format!("{}{}", GLCOREARB_H, header));
+    let found = procs.iter().find(|p| p.id == "glTypedefOnlyEXT").unwrap();
+    assert_eq!(found.pfn, "PFNGLTYPEDEFONLYEXTPROC");
+    assert!(!procs.iter().any(|p| p.pfn == "PFNGLUNNAMEDEXTPROC"));
+}
+
+#[test]
+fn gen_returns_written_paths() {
+    let dir = temp_dir("gen-paths");
+    fs::create_dir_all(dir.join("src")).unwrap();
+    let procs = Gl3wExec::default().gen_procs(GLCOREARB_H);
+
+    let exec = exec_in(&dir, &[]);
+    let written = exec.gen(GLCOREARB_H, &procs, None).unwrap();
+    assert_eq!(written, vec![dir.join("src/gl3w.h"), dir.join("src/gl3w.c")]);
+
+    let exec = Gl3wExec { path_gl3w: Gl3wPath::Single(dir.join("src/single.h")), ..exec_in(&dir, &[]) };
+    let written = exec.gen(GLCOREARB_H, &procs, None).unwrap();
+    assert_eq!(written, vec![dir.join("src/single.h")]);
+}
+
+#[test]
+fn version_macros_match_highest_block() {
+    let header = header_with(&[]);
+    assert!(header.contains("#define GL3W_GL_VERSION_MAJOR 4\n"));
+    assert!(header.contains("#define GL3W_GL_VERSION_MINOR 5\n"));
+    assert!(header.contains("/* Highest OpenGL version declared by glcorearb.h */\n"));
+
+    // Named after the header the profile parses
+    let header = header_with(&["--profile", "compatibility"]);
+    assert!(header.contains("/* Highest OpenGL version declared by glext.h */\n#define GL3W_GL_VERSION_MAJOR 4\n"));
+}
+
+#[test]
+fn compatibility_includes_gl_and_glext() {
+    let header = header_with(&["--profile", "compatibility"]);
+    assert!(header.contains("#include <GL/gl.h>\n#include <GL/glext.h>\n"));
+    assert!(!header.contains("<GL/glcorearb.h>"));
+}
+
+#[test]
+fn verify_compatibility_from_compressed_cache() {
+    let dir = temp_dir("verify-compat");
+    let exec = generate(&dir, &["--profile", "compatibility", "--compress-cache", "--no-timestamp"]);
+    exec.verify(GLCOREARB_H).unwrap();
+}
+
+#[test]
+fn check_stale_warns_on_new_etag() {
+    let dir = temp_dir("check-stale");
+    let (url, requests) = serve(vec![
+        ("/glcorearb.h", response("200 OK", &["ETag: \"new\""], "")),
+        ("/glcorearb.h", response("304 Not Modified", &["ETag: \"old\""], ""))
+    ]);
+    let exec = exec_in(&dir, &["--url", &format!("{}/glcorearb.h", url), "--check-stale"]);
+    cache(&exec, GLCOREARB_H);
+    File::create(etag_path(&exec.path_glcorearb)).unwrap().write_all(b"\"old\"").unwrap();
+
+    let outdated = format!("cached {} is outdated; run with --no-cache to refresh", exec.path_glcorearb.display());
+    assert_eq!(warnings(|| assert_eq!(exec.get_glcorearb_h().unwrap(), GLCOREARB_H)), vec![outdated]);
+    let request = requests.recv().unwrap();
+    assert!(request.starts_with("HEAD /glcorearb.h "));
+    assert!(request.contains("If-None-Match: \"old\"\r\n"));
+    assert!(warnings(|| assert_eq!(exec.get_glcorearb_h().unwrap(), GLCOREARB_H)).is_empty());
+}
+
+#[test]
+fn require_context_checks_backend() {
+    let native = source_with(&["--require-context"]);
+    assert!(native.contains("dlsym(libgl, \"glXGetCurrentContext\")"));
+    assert!(native.contains("\tif (!has_context()) {\n"));
+
+    let glfw = source_with(&["--require-context", "--loader", "glfw"]);
+    assert!(glfw.contains("return glfwGetCurrentContext() != NULL;"));
+
+    assert!(!source_with(&[]).contains("has_context"));
+}
+
+#[test]
+fn bare_file_names_need_no_directory() {
+    // Written to the current directory, which always exists
+    create_parent_dir(Path::new("gl3w.h")).unwrap();
+
+    let dir = temp_dir("parent-dir");
+    create_parent_dir(&dir.join("out/GL/gl3w.h")).unwrap();
+    assert!(dir.join("out/GL").is_dir());
+}
+
+#[test]
+fn get_proc_address_is_declared_and_defined() {
+    assert!(header_with(&[]).contains("GL3WglProc gl3wGetProcAddress(const char *proc);\n"));
+    let source = source_with(&[]);
+    assert!(source.contains("GL3WglProc gl3wGetProcAddress(const char *proc)\n{\n\treturn get_proc(proc);\n}"));
+}
+
+#[test]
+fn extensions_are_listed_sorted() {
+    let header = format!("{}{}", VENDOR_H, GLCOREARB_H);
+    assert_eq!(Gl3wExec::default().gen_extensions(&header),
+               vec!["GL_ARB_debug_output", "GL_EXT_vendor_thing", "GL_NV_texture_barrier"]);
+}
+
+#[test]
+fn extra_headers_are_fetched_and_merged() {
+    let dir = temp_dir("extra-headers");
+    let other = VENDOR_H.replace("vendor_thing", "other_thing")
+                        .replace("VendorThing", "OtherThing")
+                        .replace("VENDORTHING", "OTHERTHING");
+    let (url, _) = serve(vec![
+        ("/vendor.h", response("200 OK", &[], VENDOR_H)),
+        ("/other.h", response("200 OK", &[], &other))
+    ]);
+    let vendor_url = format!("{}/vendor.h", url);
+    let other_url = format!("{}/other.h", url);
+    let exec = exec_in(&dir, &["--extra-header", &vendor_url, "--extra-header", &other_url, "--max-concurrency", "2"]);
+    cache(&exec, GLCOREARB_H);
+
+    let header = exec.get_glcorearb_h().unwrap();
+    assert!(header.contains(VENDOR_H) && header.contains(&other));
+    let ids = exec.gen_procs(&header).into_iter().map(|p| p.id).collect::<Vec<_>>();
+    assert!(ids.contains(&"glVendorThingEXT".to_string()));
+    assert!(ids.contains(&"glOtherThingEXT".to_string()));
+    assert!(dir.join("include/GL/vendor.h").exists() && dir.join("include/GL/other.h").exists());
+}
+
+#[test]
+fn header_only_is_static_inline() {
+    let dir = temp_dir("header-only");
+    let exec = generate(&dir, &["--header-only", "--no-timestamp"]);
+    let (header, _) = generated(&exec);
+    assert!(header.contains("static inline int gl3wInit(void);\n"));
+    assert!(header.contains("static inline GL3WglProc gl3wGetProcAddress(const char *proc);\n"));
+    assert!(!dir.join("src/gl3w.c").exists());
+    exec.verify(GLCOREARB_H).unwrap();
+}
+
+#[test]
+fn name_map_renames_one_function() {
+    let dir = temp_dir("name-map");
+    let map = fixture(&dir, "names.txt", "# renamed for the engine\nglClear engineClear\n");
+    let exec = exec(&["--name-map", &map]);
+    let procs = exec.gen_procs(GLCOREARB_H);
+    assert_eq!(procs.iter().find(|p| p.id == "glClear").unwrap().var, "engineClear");
+    assert_eq!(procs.iter().find(|p| p.id == "glViewport").unwrap().var, "gl3wViewport");
+
+    let header = exec.generate_header(GLCOREARB_H, &procs, None);
+    assert!(header.contains(" engineClear;\n"));
+    assert!(header.contains(" gl3wViewport;\n"));
+    assert!(!header.contains(" gl3wClear;"));
+}
+
+#[test]
+fn name_map_rejects_bad_names() {
+    let dir = temp_dir("name-map-bad");
+    let bad = fixture(&dir, "bad.txt", "glViewport engineViewport\nglClear 1bad-name\n");
+    assert_eq!(exec_err(&["--name-map", &bad]), format!("{}:2: `1bad-name` is not a C identifier", bad));
+
+    let twice = fixture(&dir, "twice.txt", "glClear engineClear\n\nglClear otherClear\n");
+    assert_eq!(exec_err(&["--name-map", &twice]), format!("{}:3: `glClear` is already mapped", twice));
+}
+
+#[test]
+fn nested_guards_keep_their_block() {
+    let header = "\
+#ifndef GL_EXT_outer
+#define GL_EXT_outer 1
+#if defined(_WIN32)
+#ifdef GL_GLEXT_PROTOTYPES
+GLAPI void APIENTRY glWin32OnlyEXT (void);
+#endif
+#endif
+#ifndef GL_EXT_inner
+#define GL_EXT_inner 1
+GLAPI void APIENTRY glInnerEXT (void);
+#endif /* GL_EXT_inner */
+GLAPI void APIENTRY glOuterEXT (void);
+#endif /* GL_EXT_outer */
+GLAPI void APIENTRY glOutsideEXT (void);
+";
+    let procs = Gl3wExec::default().gen_procs(header);
+    let block = |id: &str| procs.iter().find(|p| p.id == id).unwrap().block.clone();
+    assert_eq!(block("glWin32OnlyEXT"), Some("GL_EXT_outer".to_string()));
+    assert_eq!(block("glInnerEXT"), Some("GL_EXT_inner".to_string()));
+    assert_eq!(block("glOuterEXT"), Some("GL_EXT_outer".to_string()));
+    assert_eq!(block("glOutsideEXT"), None);
+}
+
+#[test]
+fn match_keeps_matching_functions() {
+    // gl3wInit needs glGetIntegerv
+    assert_eq!(ids(&exec(&["--match", "^glBuffer"])), vec!["glBufferData", "glBufferSubData", "glGetIntegerv"]);
+    assert!(!ids(&exec(&["--exclude-match", "^glBuffer"])).iter().any(|id| id.starts_with("glBuffer")));
+}
+
+#[test]
+fn smoke_test_prints_version_string() {
+    let exec = exec(&["--emit-smoke-test", "smoke.c"]);
+    let smoke = gen_string(|out| gen_smoke_test(out, &exec));
+    assert!(smoke.contains("#include <GL/gl3w.h>\n"));
+    assert!(smoke.contains("\tif (gl3wInit()) {\n"));
+    assert!(smoke.contains("\telse if (!glGetString) {\n"));
+    assert!(smoke.contains("glGetString(GL_VERSION)"));
+}
+
+#[test]
+fn smoke_test_needs_get_string() {
+    let dir = temp_dir("smoke-filtered");
+    let smoke = dir.join("smoke.c");
+    let exec = exec_in(&dir, &["--emit-smoke-test", smoke.to_str().unwrap(), "--match", "^glClear$"]);
+    cache(&exec, GLCOREARB_H);
+    let (_, e) = run(&exec).unwrap_err();
+    assert_eq!(e.to_string(), "--emit-smoke-test needs glGetString, which is not generated");
+    assert!(!smoke.exists());
+}
+
+#[test]
+fn since_keeps_newer_versions() {
+    assert!(ids(&exec(&[])).contains(&"glVertexAttribDivisor".to_string()));
+    assert_eq!(ids(&exec(&["--since", "4.0"])),
+               vec!["glClipControl", "glCreateBuffers", "glGetIntegerv", "glMinSampleShading"]);
+}
+
+#[test]
+fn json_errors_exit_non_zero() {
+    // Failing exits, so the failure runs in a child process running only
+    // this test
+    if env::var("GL3W_NATIVE_TEST_CHILD").is_ok() {
+        run_cli(vec!["--json-errors".to_string(), "--no-network".to_string()].into_iter());
+        unreachable!("the fetch should have failed");
+    }
+
+    let output = run_child("json_errors_exit_non_zero", &temp_dir("json-errors"));
+    assert_eq!(output.status.code(), Some(1));
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let error = stderr.lines().find(|line| line.starts_with('{')).unwrap();
+    assert!(error.starts_with(&format!(r#"{{"stage": "fetch", "kind": "Other", "message": "failed to fetch {}: "#, URL_GLCOREARB)));
+    assert!(error.ends_with(r#"use --input or populate the cache first"}"#));
+}
+
+#[test]
+fn fetch_only_writes_only_the_cache() {
+    let dir = temp_dir("fetch-only");
+    let (url, _) = serve(vec![("/glcorearb.h", response("200 OK", &[], GLCOREARB_H))]);
+    let exec = exec_in(&dir, &["--fetch-only", "--url", &format!("{}/glcorearb.h", url)]);
+    run(&exec).unwrap();
+    assert_eq!(read(&exec.path_glcorearb), GLCOREARB_H);
+    assert!(!dir.join("src").exists());
+}
+
+#[test]
+fn commands_do_not_combine() {
+    assert_eq!(exec_err(&["--fetch-only", "--dry-diff"]), "--dry-diff cannot be used with --fetch-only");
+    assert_eq!(exec_err(&["list-procs", "verify"]), "verify cannot be used with list-procs");
+    assert_eq!(exec_err(&["--fetch-only", "--input", "glcorearb.h"]), "--fetch-only cannot be used with --input");
+    assert_eq!(exec(&["--append-extension", "GL_A", "--append-extension", "GL_B"]).command, Command::AppendExtensions);
+}
+
+#[test]
+fn one_thread_runs_in_order_on_the_caller() {
+    assert_eq!(exec(&["--threads", "1"]).threads, 1);
+
+    let caller = thread::current().id();
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let seen = order.clone();
+    let threads = parallel_map(1, vec![1, 2, 3], move |i| {
+        seen.lock().unwrap().push(i);
+        thread::current().id()
+    });
+    assert_eq!(threads, vec![caller; 3]);
+    assert_eq!(*order.lock().unwrap(), vec![1, 2, 3]);
+
+    // Results keep the order of the items either way
+    assert_eq!(parallel_map(4, (0..32).collect(), |i| i * 2), (0..32).map(|i| i * 2).collect::<Vec<_>>());
+    assert!(parallel_map(2, vec![1, 2], |_| thread::current().id()).iter().all(|id| *id != caller));
+}
+
+#[test]
+fn html_responses_fail() {
+    let dir = temp_dir("html");
+    let page = "<!DOCTYPE html>\n<html><body>Are you a robot?</body></html>\n";
+    let (url, _) = serve(vec![
+        ("/glcorearb.h", response("200 OK", &["Content-Type: text/html; charset=utf-8"], page)),
+        ("/glcorearb.h", response("200 OK", &[], page)),
+        ("/glcorearb.h", response("200 OK", &["Content-Type: text/html"], GLCOREARB_H))
+    ]);
+    let url = format!("{}/glcorearb.h", url);
+
+    let exec = exec_in(&dir, &["--url", &url]);
+    let (stage, e) = run(&exec).unwrap_err();
+    assert_eq!(stage, "fetch");
+    assert_eq!(e.to_string(), format!("{} returned an HTML page instead of a header; use --allow-html to accept it", url));
+    assert!(!exec.path_glcorearb.exists());
+
+    // Without a type the page is still not a header
+    let (_, e) = run(&exec).unwrap_err();
+    assert_eq!(e.to_string(), format!("{} returned {} bytes without a single preprocessor line, which is not a header",
+                                      url, page.len()));
+
+    let exec = exec_in(&dir, &["--url", &url, "--allow-html"]);
+    assert_eq!(exec.get_glcorearb_h().unwrap(), GLCOREARB_H);
+}
+
+#[test]
+fn init2_loads_through_the_resolver() {
+    assert!(header_with(&["--init2"]).contains("int gl3wInit2(GL3WGetProcAddressProc proc);\n"));
+
+    let source = source_with(&["--init2"]);
+    assert!(source.contains("int gl3wInit2(GL3WGetProcAddressProc proc)\n{\n\tload_procs_from(proc);\n"));
+    assert!(source.contains("static void load_procs(void)\n{\n\tload_procs_from(get_proc);\n}"));
+    assert!(source.contains("static void load_procs_from(GL3WGetProcAddressProc proc)\n{\n"));
+    assert!(source.contains(r#"gl3wClear = (PFNGLCLEARPROC) proc("glClear");"#));
+    assert!(!source.contains(r#"get_proc("glClear")"#));
+}
+
+#[test]
+fn gzipped_input_matches_plain() {
+    let dir = temp_dir("gzip-input");
+    let plain = fixture(&dir, "glcorearb.h", GLCOREARB_H);
+    let gz = dir.join("glcorearb.h.gz");
+    write_header(&gz, GLCOREARB_H).unwrap();
+
+    let from_plain = exec(&["--input", &plain]);
+    let from_gz = exec(&["--input", gz.to_str().unwrap()]);
+    let header = from_gz.get_glcorearb_h().unwrap();
+    assert_eq!(header, from_plain.get_glcorearb_h().unwrap());
+    assert_eq!(from_gz.gen_procs(&header), from_plain.gen_procs(GLCOREARB_H));
+}
+
+#[test]
+fn verify_passes_until_edited() {
+    let dir = temp_dir("verify-current");
+    let generated = generate(&dir, &[]);
+    let verify = exec_in(&dir, &["verify"]);
+    assert!(run(&verify).is_ok());
+
+    let path_h = match generated.path_gl3w {
+        Gl3wPath::Separate(ref path_h, _) => path_h.clone(),
+        Gl3wPath::Single(_) => unreachable!()
+    };
+    let edited = read(&path_h).replace("int gl3wInit(void);", "int gl3wInit(int);");
+    File::create(&path_h).unwrap().write_all(edited.as_bytes()).unwrap();
+    let stale = verify.check_current(GLCOREARB_H, &verify.gen_procs(GLCOREARB_H), verify.detected_version(GLCOREARB_H)).unwrap();
+    assert_eq!(stale.len(), 1);
+    assert!(stale[0].contains("is out of date: 1 line(s) differ"));
+    assert!(run(&verify).is_err());
+}
+
+#[test]
+fn proc_regex_reads_other_formats() {
+    let header = "\
+GL_EXPORT(void, glClear, (GLbitfield mask));
+GL_EXPORT(void, glViewport, (GLint x, GLint y, GLsizei width, GLsizei height));
+GL_EXPORT(void, gl, (void));
+GL_EXPORT(void, éclair, (void));
+GL_EXPORT(void, , (void));
+";
+    let exec = exec(&["--proc-regex", r"GL_EXPORT\(\w+, ([^,\s]+)?, "]);
+    let mut procs = Vec::new();
+    let warned = warnings(|| procs = exec.gen_procs(header));
+    assert_eq!(procs.iter().map(|p| &p.id[..]).collect::<Vec<_>>(), ["glClear", "glViewport"]);
+    assert_eq!(procs[0].var, "gl3wClear");
+    assert_eq!(warned, [
+        "--proc-regex captured `gl`, which is not a GL function, skipping",
+        "--proc-regex captured `éclair`, which is not a GL function, skipping",
+        "--proc-regex matched `GL_EXPORT(void, , ` without capturing a name, skipping"
+    ]);
+
+    assert!(exec_err(&["--proc-regex", r"GL_EXPORT\(\w+, \w+"]).contains("must capture the function name"));
+}
+
+#[test]
+fn compile_commands_builds_the_source() {
+    let dir = temp_dir("compile-commands");
+    let path = dir.join("compile_commands.json");
+    generate(&dir, &["--compile-commands", path.to_str().unwrap()]);
+
+    let commands = read(&path);
+    let file = dir.join("src/gl3w.c").display().to_string();
+    assert!(commands.contains(&format!(r#""file": "{}","#, file)));
+    assert!(commands.contains(&format!(r#""arguments": ["cc", "-I{}", "-c", "{}"]"#, dir.join("include").display(), file)));
+}
+
+#[test]
+fn one_run_writes_every_output() {
+    let dir = temp_dir("outputs");
+    let enums = dir.join("enums.json");
+    let typedefs = dir.join("typedefs.h");
+    let exec = generate(&dir, &["--dump-enums", enums.to_str().unwrap(), "--dump-typedefs", typedefs.to_str().unwrap()]);
+
+    let (header, source) = generated(&exec);
+    assert!(header.contains("extern PFNGLCLEARPROC"));
+    assert!(source.contains(r#"get_proc("glClear")"#));
+    assert!(read(&enums).contains(r#"{ "name": "GL_COLOR_BUFFER_BIT", "value": "0x00004000" }"#));
+    assert!(read(&typedefs).contains("typedef unsigned int GLbitfield;\n"));
+}
+
+#[test]
+fn merged_duplicates_keep_the_core_declaration() {
+    let foo = |guard: &str, param: &str| format!("\
+#ifndef __gl_{0}_h_
+#define __gl_{0}_h_ 1
+#ifndef GL_VERSION_1_0
+#define GL_VERSION_1_0 1
+GLAPI void APIENTRY glFoo ({1});
+GLAPI void APIENTRY glGetIntegerv (GLenum pname, GLint *data);
+#endif
+#endif
+", guard, param);
+    let dir = temp_dir("duplicates");
+    let glext = fixture(&dir, "glext.h", &foo("glext", "GLint foo"));
+    let core = fixture(&dir, "glcorearb.h", &foo("glcorearb", "GLuint foo"));
+    let exec = exec_in(&dir, &["--input", &glext, "--input", &core]);
+
+    let procs = exec.gen_procs(&exec.get_glcorearb_h().unwrap());
+    let procs = procs.iter().filter(|p| p.id == "glFoo").collect::<Vec<_>>();
+    assert_eq!(procs.len(), 1);
+    assert_eq!(procs[0].source, Some("glcorearb.h".to_string()));
+    assert_eq!(procs[0].proto, Some(("void".to_string(), "GLuint foo".to_string())));
+}
+
+#[test]
+fn header_carries_the_generator_version() {
+    let header = header_with(&[]);
+    assert!(header.contains(&format!("#define GL3W_NATIVE_GENERATOR_VERSION \"{}\"\n", env!("CARGO_PKG_VERSION"))));
+    assert_eq!(exec(&["--print-version-only"]).command, Command::PrintVersion);
+}
+
+#[test]
+fn revalidate_downloads_only_when_changed() {
+    let head = |length: usize| format!("HTTP/1.1 200 OK\r\nConnection: close\r\nContent-Length: {}\r\n\r\n", length);
+
+    let dir = temp_dir("revalidate-current");
+    let (url, requests) = serve(vec![("/glcorearb.h", head(GLCOREARB_H.len()))]);
+    let exec = exec_in(&dir, &["--url", &format!("{}/glcorearb.h", url), "--revalidate"]);
+    cache(&exec, GLCOREARB_H);
+    assert_eq!(exec.get_glcorearb_h().unwrap(), GLCOREARB_H);
+    assert!(requests.recv().unwrap().starts_with("HEAD /glcorearb.h "));
+    assert!(requests.recv().is_err());
+
+    let dir = temp_dir("revalidate-changed");
+    let changed = format!("{}\n", GLCOREARB_H);
+    let (url, requests) = serve(vec![
+        ("/glcorearb.h", head(changed.len())),
+        ("/glcorearb.h", response("200 OK", &[], &changed))
+    ]);
+    let exec = exec_in(&dir, &["--url", &format!("{}/glcorearb.h", url), "--revalidate"]);
+    cache(&exec, GLCOREARB_H);
+    assert_eq!(exec.get_glcorearb_h().unwrap(), changed);
+    assert!(requests.recv().unwrap().starts_with("HEAD /glcorearb.h "));
+    assert!(requests.recv().unwrap().starts_with("GET /glcorearb.h "));
+    assert_eq!(read_header(&exec.path_glcorearb).unwrap(), changed);
+}
+
+#[test]
+fn dlopen_falls_back_to_dlsym() {
+    let source = source_with(&["--loader", "dlopen", "--libgl-name", "lib\"GL\"??/é\n1.so"]);
+    assert!(source.contains("#define GL3W_LIBGL_NAME \"lib\\\"GL\\\"\\?\\?/\\303\\251\\0121.so\"\n"));
+    assert!(source.contains("libgl = dlopen(GL3W_LIBGL_NAME, RTLD_LAZY | RTLD_GLOBAL);"));
+    assert!(source.contains("\tif (!res && libgl)\n\t\tres = (GL3WglProc) dlsym(libgl, proc);"));
+    assert!(source.contains("res = (GL3WglProc) GetProcAddress(libgl, proc);"));
+}
+
+#[test]
+fn from_nm_keeps_referenced_functions() {
+    let dir = temp_dir("from-nm");
+    let nm = fixture(&dir, "app.nm", "\
+0000000000001139 T main
+                 U glClear
+                 U _glViewport
+                 U gl3wGetIntegerv
+0000000000004010 B gl3wBindBuffer
+                 U glXGetProcAddressARB
+                 U glUnknownThing@LIB_1.0
+                 U printf@GLIBC_2.2.5
+");
+    let exec = exec(&["--from-nm", &nm]);
+    assert_eq!(ids(&exec), ["glBindBuffer", "glClear", "glGetIntegerv", "glViewport"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn read_only_output_writes_nothing() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = temp_dir("read-only");
+    let out = dir.join("src");
+    fs::create_dir_all(&out).unwrap();
+    fs::set_permissions(&out, fs::Permissions::from_mode(0o555)).unwrap();
+    // Permissions do not apply to root
+    if File::create(out.join("probe")).is_ok() {
+        return;
+    }
+
+    let exec = exec_in(&dir, &[]);
+    cache(&exec, GLCOREARB_H);
+    let (stage, e) = run(&exec).unwrap_err();
+    assert_eq!(stage, "generate");
+    assert_eq!(e.kind(), io::ErrorKind::PermissionDenied);
+    assert_eq!(e.to_string(), format!("cannot write to {}: permission denied", out.display()));
+    assert_eq!(fs::read_dir(&out).unwrap().count(), 0);
+}
+
+#[test]
+fn list_procs_prints_sorted_names() {
+    // The list goes to stdout, so is printed by a child process
+    if env::var("GL3W_NATIVE_TEST_CHILD").is_ok() {
+        run(&exec(&["list-procs", "--input", "glcorearb.h"])).unwrap();
+        return;
+    }
+
+    let dir = temp_dir("list-procs");
+    fixture(&dir, "glcorearb.h", GLCOREARB_H);
+    let output = run_child("list_procs_prints_sorted_names", &dir);
+    assert!(output.status.success());
+
+    let mut sorted = ids(&exec(&[]));
+    sorted.sort();
+    assert_eq!(sorted.len(), 20);
+    // The first name follows the test harness's `test ... `
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let listed = stdout.lines().filter_map(|line| line.rsplit(' ').next()).filter(|word| word.starts_with("gl"));
+    assert_eq!(listed.collect::<Vec<_>>(), sorted);
+}
+
+#[test]
+fn idempotent_init_loads_once() {
+    let source = source_with(&["--idempotent-init"]);
+    assert!(source.contains("static int load(void)\n"));
+    assert!(source.contains("int gl3wInit(void)\n{\n\tif (loaded)\n\t\treturn 0;\n\treturn gl3wReinit();\n}\n"));
+    assert!(source.contains("int gl3wReinit(void)\n{\n\tint res = load();\n\n\tloaded = res == 0;\n"));
+    assert!(header_with(&["--idempotent-init"]).contains("int gl3wReinit(void);\n"));
+    assert!(!source_with(&[]).contains("gl3wReinit"));
+}
+
+#[test]
+fn appended_defines_come_first_in_order() {
+    let header = header_with(&["--append-define", "GL3W_STATIC=1", "--append-define", "GL3W_DEBUG",
+                               "--append-define", "GL3W_NAME=\"gl=3\""]);
+    // Only the metadata comment comes before them
+    let defines = "#define GL3W_STATIC 1\n#define GL3W_DEBUG\n#define GL3W_NAME \"gl=3\"\n/*\n";
+    assert_eq!(header.find(defines), header.find(" */\n").map(|i| i + 4));
+    assert_eq!(exec_err(&["--append-define", "3D=1"]), "invalid define name `3D`");
+}
+
+#[test]
+fn detected_version_is_the_highest_marker() {
+    let exec = exec(&[]);
+    assert_eq!(exec.detected_version(GLCOREARB_H), Some((4, 5)));
+    assert_eq!(exec.detected_version("#define GL_VERSION_4_6 1\n#define GL_VERSION_99999999999_0 1\n"), Some((4, 6)));
+    assert_eq!(exec.detected_version("#define GL_VERSION_4_6 0x4600\n"), None);
+}
+
+#[test]
+fn no_write_glcorearb_leaves_no_cache() {
+    let dir = temp_dir("no-write");
+    let (url, _) = serve(vec![("/glcorearb.h", response("200 OK", &[], GLCOREARB_H))]);
+    let exec = exec_in(&dir, &["--no-write-glcorearb", "--url", &format!("{}/glcorearb.h", url)]);
+    run(&exec).unwrap();
+    assert!(generated(&exec).0.contains("extern PFNGLCLEARPROC"));
+    assert!(!exec.path_glcorearb.exists());
+    assert!(!dir.join("include").exists());
+}
+
+#[test]
+fn thread_local_pointers_use_tls() {
+    fn declaration(text: &str) -> Vec<&str> {
+        text.lines().find(|l| l.ends_with(" gl3wClear;")).unwrap().split_whitespace().collect()
+    }
+
+    let header = header_with(&["--thread-local"]);
+    assert!(header.contains(include_str!("../template/gl3w.thread_local.h")));
+    assert_eq!(declaration(&header), ["extern", "GL3W_THREAD_LOCAL", "PFNGLCLEARPROC", "gl3wClear;"]);
+    assert_eq!(declaration(&source_with(&["--thread-local"])), ["GL3W_THREAD_LOCAL", "PFNGLCLEARPROC", "gl3wClear;"]);
+
+    let header = header_with(&["--thread-local", "--header-only"]);
+    assert_eq!(declaration(&header), ["static", "GL3W_THREAD_LOCAL", "PFNGLCLEARPROC", "gl3wClear;"]);
+    assert!(!header_with(&[]).contains("GL3W_THREAD_LOCAL"));
+}
+
+#[test]
+fn stats_summarize_the_run() {
+    let dir = temp_dir("stats");
+    let path = dir.join("stats.json");
+    generate(&dir, &["--stats", path.to_str().unwrap()]);
+
+    let stats = read(&path);
+    let keys = stats.lines().filter_map(|line| line.trim().split('"').nth(1)).collect::<Vec<_>>();
+    assert_eq!(keys, ["procs", "core_procs", "extension_procs", "gl_version", "source", "content_hash", "duration_ms"]);
+    assert!(stats.contains("  \"procs\": 20,\n  \"core_procs\": 17,\n  \"extension_procs\": 3,\n  \"gl_version\": \"4.5\",\n"));
+}
+
+#[test]
+fn one_connection_serves_every_header() {
+    // `serve` closes each connection, so this server keeps them open and
+    // reports each one it accepts
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, connections) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            tx.send(()).unwrap();
+            let mut request = Vec::new();
+            let mut byte = [0];
+            while stream.read(&mut byte).unwrap_or(0) == 1 {
+                request.push(byte[0]);
+                if request.ends_with(b"\r\n\r\n") {
+                    let body = if request.starts_with(b"GET /glcorearb.h ") { GLCOREARB_H } else { VENDOR_H };
+                    write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+                    request.clear();
+                }
+            }
+        }
+    });
+
+    let dir = temp_dir("keep-alive");
+    let vendor_url = format!("{}/vendor.h", url);
+    let exec = exec_in(&dir, &["--url", &format!("{}/glcorearb.h", url), "--extra-header", &vendor_url,
+                               "--threads", "1"]);
+    let header = exec.get_glcorearb_h().unwrap();
+    assert!(header.contains(GLCOREARB_H) && header.contains(VENDOR_H));
+    assert_eq!(connections.try_iter().count(), 1);
+}
+
+#[test]
+fn pruned_enums_follow_the_kept_functions() {
+    let exec = exec(&["--prune-enums", "--match", "^gl(ClipControl|DebugMessageControlARB)$"]);
+    let enums = exec.gen_enums(GLCOREARB_H, &exec.gen_procs(GLCOREARB_H));
+    let names = enums.iter().map(|e| e.0.as_str()).collect::<Vec<_>>();
+    // glGetIntegerv is always kept, and with it the OpenGL 1.0 constants
+    assert_eq!(names, ["GL_DEPTH_BUFFER_BIT", "GL_COLOR_BUFFER_BIT", "GL_FALSE", "GL_TRUE", "GL_VERSION",
+                       "GL_LOWER_LEFT", "GL_DEBUG_OUTPUT_SYNCHRONOUS_ARB"]);
+}
+
+#[test]
+fn dry_diff_shows_changed_lines() {
+    // The diff goes to stdout, so is printed by a child process
+    if env::var("GL3W_NATIVE_TEST_CHILD").is_ok() {
+        run(&exec(&["--input", "glcorearb.h"])).unwrap();
+        let edited = read("src/gl3w.h").replace("int gl3wInit(void);", "int gl3wInit(int);");
+        File::create("src/gl3w.h").unwrap().write_all(edited.as_bytes()).unwrap();
+        run(&exec(&["--dry-diff", "--input", "glcorearb.h"])).unwrap();
+        return;
+    }
+
+    let dir = temp_dir("dry-diff");
+    fixture(&dir, "glcorearb.h", GLCOREARB_H);
+    let output = run_child("dry_diff_shows_changed_lines", &dir);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("--- src/gl3w.h\n+++ src/gl3w.h\n"));
+    assert!(stdout.contains("\n-int gl3wInit(int);\n+int gl3wInit(void);\n"));
+    assert!(!stdout.contains("--- src/gl3w.c"));
+}
+
+#[test]
+fn stale_cache_is_used_when_the_download_fails() {
+    let dir = temp_dir("stale-on-error");
+    let (url, _) = serve(vec![
+        ("/glcorearb.h", response("503 Service Unavailable", &[], "")),
+        ("/glcorearb.h", response("503 Service Unavailable", &[], ""))
+    ]);
+    let url = format!("{}/glcorearb.h", url);
+
+    let exec = exec_in(&dir, &["--no-cache", "--url", &url]);
+    cache(&exec, GLCOREARB_H);
+    assert!(exec.get_glcorearb_h().is_err());
+
+    let exec = exec_in(&dir, &["--no-cache", "--allow-stale-on-error", "--url", &url]);
+    let mut header = String::new();
+    let warned = warnings(|| header = exec.get_glcorearb_h().unwrap());
+    assert_eq!(header, GLCOREARB_H);
+    assert_eq!(warned.len(), 2);
+    assert!(warned[0].starts_with(&format!("failed to fetch {}: 503 Service Unavailable", url)));
+    assert_eq!(warned[1], format!("using the cached {}, which may be outdated", exec.path_glcorearb.display()));
+}
+
+#[test]
+fn split_headers_keep_the_pointers_internal() {
+    let exec = exec(&["--split-headers"]);
+    let procs = exec.gen_procs(GLCOREARB_H);
+    let public = exec.generate_header(GLCOREARB_H, &procs, exec.detected_version(GLCOREARB_H));
+    let internal = exec.generate_internal_header(&procs);
+    assert!(public.contains("GL3WglProc gl3wGetProcAddress(const char *proc);\n\n/* Version of gl3w-native"));
+    assert!(!public.contains("PFNGLCLEARPROC") && !public.contains("#define glClear "));
+
+    assert!(internal.contains("#include <GL/gl3w.h>\n"));
+    let declaration = internal.lines().find(|l| l.ends_with(" gl3wClear;")).unwrap();
+    assert_eq!(declaration.split_whitespace().collect::<Vec<_>>(), ["extern", "PFNGLCLEARPROC", "gl3wClear;"]);
+    assert!(internal.lines().any(|l| l.split_whitespace().collect::<Vec<_>>() == ["#define", "glClear", "gl3wClear"]));
+}
+
+#[test]
+fn registry_ref_pins_the_mirror_url() {
+    let exec = exec(&["--registry-ref", "a1b2c3d"]);
+    assert_eq!(exec.url_glcorearb, format!("{}/a1b2c3d/api/GL/glcorearb.h", URL_REGISTRY_GITHUB));
+    assert_eq!(exec.url_glext, format!("{}/a1b2c3d/api/GL/glext.h", URL_REGISTRY_GITHUB));
+    assert_eq!(exec_err(&["--registry-ref", "main?x"]), "invalid --registry-ref `main?x`");
+    assert_eq!(exec_err(&["--registry-ref", "v1", "--url", "http://example.com/glcorearb.h"]),
+               "--registry-ref cannot be used with --url");
+}
+
+#[test]
+fn self_contained_sdl2_compiles_alone() {
+    let dir = temp_dir("self-contained-sdl2");
+    let exec = generate(&dir, &["--self-contained", "--loader", "sdl2"]);
+    let (header, _) = generated(&exec);
+    assert!(!dir.join("src/gl3w.c").exists());
+    assert!(!header.contains("#include <GL/glcorearb.h>"));
+    assert!(header.contains("typedef void (APIENTRYP PFNGLCLEARPROC) (GLbitfield mask);"));
+
+    let implementation = header.find("#if defined(GL3W_IMPLEMENTATION)").unwrap();
+    let sdl = header.find("\treturn (GL3WglProc) SDL_GL_GetProcAddress(proc);").unwrap();
+    let load = header.find("gl3wClear = (PFNGLCLEARPROC) get_proc(\"glClear\");").unwrap();
+    assert!(implementation < sdl && sdl < load);
+
+    // Only SDL itself is needed, which is stubbed
+    fixture(&dir, "stub/SDL2/SDL.h", "void *SDL_GL_GetProcAddress(const char *proc);\n");
+    let main = fixture(&dir, "main.c", "#define GL3W_IMPLEMENTATION\n#include \"src/gl3w.h\"\n");
+    compile::Compiler::discover(compile::Lang::C).check(Path::new(&main), &[dir.join("stub")], &[]).unwrap();
+}
+
+#[test]
+fn many_prototypes_parse() {
+    let mut header = String::from("#ifndef GL_VERSION_1_0\n#define GL_VERSION_1_0 1\n");
+    for i in 0..50_000 {
+        header.push_str(&format!("#define GL_STRESS_{} 0x{:x}\n", i, i));
+        header.push_str(&format!("GLAPI void APIENTRY glStress{} (GLint value);\n", i));
+    }
+    header.push_str("#endif /* GL_VERSION_1_0 */\n");
+
+    let start = time::precise_time_ns();
+    let exec = exec(&[]);
+    assert_eq!(exec.gen_procs(&header).len(), 50_000);
+    assert_eq!(exec.gen_enums(&header, &[]).len(), 50_000);
+    assert!(time::precise_time_ns() - start < 60_000_000_000);
+}
+
+#[test]
+fn sort_by_pfn_orders_by_typedef() {
+    // glGetStringi's PFNGLGETSTRINGIPROC sorts before PFNGLGETSTRINGPROC,
+    // unlike the names
+    let by_id = ids(&exec(&[]));
+    let by_pfn = exec(&["--sort-by", "pfn"]).gen_procs(GLCOREARB_H).into_iter().map(|p| p.pfn).collect::<Vec<_>>();
+    let mut sorted = by_pfn.clone();
+    sorted.sort();
+    assert_eq!(by_pfn, sorted);
+    let position = |id: &str| by_id.iter().position(|i| i == id).unwrap();
+    assert!(position("glGetString") < position("glGetStringi"));
+    let position = |pfn: &str| by_pfn.iter().position(|p| p == pfn).unwrap();
+    assert!(position("PFNGLGETSTRINGIPROC") < position("PFNGLGETSTRINGPROC"));
+
+    let header = header_with(&["--sort-by", "pfn"]);
+    assert!(header.find(" gl3wGetStringi;").unwrap() < header.find(" gl3wGetString;").unwrap());
+}
+
+#[test]
+fn annotated_versions_follow_declarations() {
+    fn annotation<'a>(header: &'a str, var: &str) -> &'a str {
+        let line = header.lines().find(|l| l.contains(&format!(" {};", var))).unwrap();
+        line.rsplit("/* ").next().unwrap()
+    }
+
+    let header = header_with(&["--annotate-versions"]);
+    assert_eq!(annotation(&header, "gl3wFenceSync"), "OpenGL 3.2 */");
+    assert_eq!(annotation(&header, "gl3wDebugMessageCallbackARB"), "GL_ARB_debug_output */");
+    assert!(!header_with(&[]).contains("/* OpenGL 3.2 */"));
+}
+
+#[test]
+fn basename_and_source_ext_name_the_files() {
+    let dir = temp_dir("basename");
+    let exec = generate(&dir, &["--basename", "glloader", "--source-ext", "cpp"]);
+    assert!(dir.join("src/glloader.h").exists() && dir.join("src/glloader.cpp").exists());
+    assert!(!dir.join("src/gl3w.h").exists());
+    assert!(generated(&exec).1.contains("#include <GL/glloader.h>\n"));
+}
+
+#[test]
+fn changelog_lists_changes_since_the_last_manifest() {
+    let dir = temp_dir("changelog");
+    let manifest = dir.join("gl3w.manifest");
+    let changelog = dir.join("CHANGELOG-gl.md");
+    let args = ["--manifest", manifest.to_str().unwrap(), "--changelog", changelog.to_str().unwrap(), "--reproducible"];
+    let exec = generate(&dir, &args);
+    assert!(!changelog.exists());
+
+    let changed = GLCOREARB_H.replace("glViewport ", "glViewportNV ").replace("VIEWPORTPROC", "VIEWPORTNVPROC")
+                             .replace("#ifdef __cplusplus\n}", &format!("{}\n#ifdef __cplusplus\n}}", VENDOR_H));
+    cache(&exec, &changed);
+    run(&exec).unwrap();
+    assert_eq!(read(&changelog), format!(
+        "## {}\n\n### Added\n\n- `glVendorThingEXT`\n- `glViewportNV`\n\n### Removed\n\n- `glViewport`\n\n", exec.source()
+    ));
+
+    // Nothing changed since
+    run(&exec).unwrap();
+    assert_eq!(read(&changelog).matches("### Added").count(), 1);
+}
+
+#[test]
+fn quiet_errors_json_succeeds_silently() {
+    if env::var("GL3W_NATIVE_TEST_CHILD").is_ok() {
+        let args = ["--quiet-errors-json", "--input", "glcorearb.h", "--insecure"];
+        run_cli(args.iter().map(|arg| arg.to_string()));
+        return;
+    }
+
+    let dir = temp_dir("quiet-errors-json-success");
+    fixture(&dir, "glcorearb.h", GLCOREARB_H);
+    let output = run_child("quiet_errors_json_succeeds_silently", &dir);
+    assert!(output.status.success());
+    assert!(dir.join("src/gl3w.c").exists());
+    assert!(!String::from_utf8(output.stdout).unwrap().contains("generated"));
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), "");
+}
+
+#[test]
+fn quiet_errors_json_fails_with_only_json() {
+    if env::var("GL3W_NATIVE_TEST_CHILD").is_ok() {
+        run_cli(vec!["--quiet-errors-json".to_string(), "--input".to_string(), "missing.h".to_string()].into_iter());
+        unreachable!("reading the input should have failed");
+    }
+
+    let output = run_child("quiet_errors_json_fails_with_only_json", &temp_dir("quiet-errors-json-failure"));
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.lines().count(), 1);
+    assert!(stderr.starts_with(r#"{"stage": "fetch", "kind": "NotFound", "message": "#));
+    assert!(stderr.ends_with("}\n"));
+}
+
+#[test]
+fn win32_falls_back_to_opengl32() {
+    let source = source_with(&["--loader", "win32"]);
+    let wgl = source.find("\tres = wglGetProcAddress(proc);\n").unwrap();
+    let fallback = source.find("\t\treturn (GL3WglProc) GetProcAddress(libgl, proc);\n").unwrap();
+    assert!(wgl < fallback);
+    assert!(source.contains("libgl = LoadLibraryA(\"opengl32.dll\");"));
+}
+
+#[test]
+fn no_deprecated_drops_removed_functions() {
+    let header = GLCOREARB_H.replace("#ifdef __cplusplus\n}", "\
+#ifndef GL_VERSION_1_4
+#define GL_VERSION_1_4 1
+GLAPI void APIENTRY glFogCoordf (GLfloat coord);
+GLAPI void APIENTRY glBlendColor (GLfloat red, GLfloat green, GLfloat blue, GLfloat alpha);
+#endif /* GL_VERSION_1_4 */
+
+#ifdef __cplusplus\n}");
+    let names = |args: &[&str]| exec(args).gen_procs(&header).into_iter().map(|p| p.id).collect::<Vec<_>>();
+    assert!(names(&[]).contains(&"glFogCoordf".to_string()));
+
+    let kept = names(&["--no-deprecated"]);
+    assert!(!kept.contains(&"glFogCoordf".to_string()));
+    assert!(kept.contains(&"glBlendColor".to_string()));
+    assert_eq!(kept.len(), 21);
+}
+
+#[test]
+fn runtime_info_fills_gl3w_version() {
+    assert!(header_with(&["--runtime-info"]).contains(include_str!("../template/gl3w.api.runtime.h")));
+    let source = source_with(&["--runtime-info"]);
+    assert!(source.contains("struct gl3w_version gl3wVersion;\n"));
+    assert!(source.contains("\t\tgl3wVersion.vendor = (const char *) glGetString(GL_VENDOR);\n"));
+
+    // Without glGetString the struct is left out
+    let exec = exec(&["--runtime-info", "--exclude-match", "^glGetString$"]);
+    let procs = exec.gen_procs(GLCOREARB_H);
+    assert!(!exec.generate_source(&procs).contains("gl3wVersion"));
+    assert!(!exec.generate_header(GLCOREARB_H, &procs, None).contains("gl3w_version"));
+    assert!(!source_with(&[]).contains("gl3wVersion"));
+}
+
+#[test]
+fn exact_match_layout_is_stable() {
+    // Regenerating must keep matching the file committed earlier; only the
+    // generator version may differ
+    let args = ["--order", "source", "--align", "0", "--no-timestamp"];
+    let golden = include_str!("gl3w.golden.h").replace("x.y.z", env!("CARGO_PKG_VERSION"));
+    assert_eq!(header_with(&args), golden);
+
+    let crlf = header_with(&[&args[..], &["--line-endings", "crlf"]].concat());
+    assert_eq!(crlf, golden.replace('\n', "\r\n"));
+}
+
+#[test]
+fn compat_prefix_aliases_the_new_names() {
+    let words = |line: &str| line.split_whitespace().map(|w| w.to_string()).collect::<Vec<_>>();
+    let header = header_with(&["--prefix", "myapp", "--compat-prefix", "gl3w"]);
+    let lines = header.lines().map(&words).collect::<Vec<_>>();
+    assert!(lines.contains(&words("extern PFNGLCLEARPROC myappClear;")));
+    assert!(lines.contains(&words("#define glClear myappClear")));
+    assert!(lines.contains(&words("#define gl3wClear myappClear")));
+    assert!(!header.contains("extern PFNGLCLEARPROC gl3wClear"));
+
+    // An alias matching the prefix would define a name as itself
+    assert!(!header_with(&["--compat-prefix", "gl3w"]).contains("Compatibility names"));
+}
+
+#[test]
+fn other_export_macros_are_parsed() {
+    let header = "\
+WINGDIAPI void APIENTRY glFinish (void);
+GL_APICALL void GL_APIENTRY glFlush (void);
+GLAPI void APIENTRY glGetIntegerv (GLenum pname, GLint *data);
+";
+    let procs = exec(&[]).gen_procs(header);
+    assert_eq!(procs.iter().map(|p| &p.id[..]).collect::<Vec<_>>(), ["glFinish", "glFlush", "glGetIntegerv"]);
+    assert_eq!(procs[0].proto, Some(("void".to_string(), "void".to_string())));
+}
+
+#[test]
+fn pch_header_holds_only_stable_content() {
+    let exec = exec(&["--pch-header"]);
+    let procs = exec.gen_procs(GLCOREARB_H);
+    let pch = exec.generate_pch_header(&procs);
+    assert!(pch.contains("#include <GL/glcorearb.h>\n"));
+    assert!(!pch.lines().any(|l| l.starts_with("extern ") || l.starts_with("#define gl") || l.contains("gl3wInit")));
+    assert_eq!(pch, exec.generate_pch_header(&procs[..5]));
+
+    let header = exec.generate_header(GLCOREARB_H, &procs, None);
+    assert!(header.contains("#include <GL/gl3w_pch.h>\n"));
+    assert!(!header.contains("#include <GL/glcorearb.h>"));
+    assert!(header.contains(" gl3wClear;\n"));
+}
+
+#[test]
+#[should_panic(expected = "`Clear` is not an OpenGL function name")]
+fn proc_names_must_start_with_gl() {
+    Proc::new("Clear");
+}
+
+#[test]
+fn appended_extension_is_inserted_in_order() {
+    let dir = temp_dir("append-extension");
+    let full = generated(&generate(&dir, &["--no-timestamp"]));
+    let exec = generate(&dir, &["--no-timestamp", "--exclude-match", "ARB$"]);
+    assert!(!generated(&exec).0.contains("gl3wDebugMessageCallbackARB"));
+
+    run(&exec_in(&dir, &["--append-extension", "GL_ARB_debug_output"])).unwrap();
+    // Everything but the function count matches generating them all
+    let counted = |text: &str| text.replace(" * procs: 18\n", " * procs: 20\n");
+    let (header, source) = generated(&exec);
+    assert_eq!(counted(&header), full.0);
+    assert_eq!(counted(&source), full.1);
+
+    // Appending again changes nothing
+    run(&exec_in(&dir, &["--append-extension", "GL_ARB_debug_output"])).unwrap();
+    assert_eq!(generated(&exec), (header, source));
+}
+
+#[test]
+fn check_url_reports_the_final_url() {
+    // The report goes to stdout, so is printed by a child process
+    if env::var("GL3W_NATIVE_TEST_CHILD").is_ok() {
+        let (url, _) = serve(vec![
+            ("/glcorearb.h", response("301 Moved Permanently", &["Location: /moved/glcorearb.h"], "")),
+            ("/moved/glcorearb.h", response("200 OK", &["Content-Type: text/plain"], ""))
+        ]);
+        run(&exec(&["check-url", "--url", &format!("{}/glcorearb.h", url)])).unwrap();
+        return;
+    }
+
+    let output = run_child("check_url_reports_the_final_url", &temp_dir("check-url"));
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let report = &stdout[stdout.find("http://").unwrap()..];
+    let first = report.lines().next().unwrap();
+    let url = &first[..first.len() - "/glcorearb.h".len()];
+    assert!(report.starts_with(&format!("{}/glcorearb.h\n  final url: {}/moved/glcorearb.h\n  status: 200 OK\n  \
+                                         content-type: text/plain\n  content-length: 0\n", url, url)));
+}
+
+#[test]
+fn assert_loaded_names_each_function() {
+    let define = |header: &str, name: &str| header.lines().find(|l| l.starts_with(&format!("#define {} ", name)))
+                                                  .unwrap().split_whitespace().skip(2).collect::<Vec<_>>().join(" ");
+    let header = header_with(&["--assert-loaded"]);
+    assert!(header.contains(include_str!("../template/gl3w.assert_loaded.h")));
+    assert_eq!(define(&header, "glClear"), r#"GL3W_ASSERT_LOADED(gl3wClear, "glClear")"#);
+    assert_eq!(define(&header, "glFenceSync"), r#"GL3W_ASSERT_LOADED(gl3wFenceSync, "glFenceSync")"#);
+    assert_eq!(define(&header_with(&[]), "glClear"), "gl3wClear");
+}
+
+#[test]
+fn head_only_validate_checks_the_status() {
+    let dir = temp_dir("head-only-validate");
+    let (url, requests) = serve(vec![
+        ("/glcorearb.h", response("200 OK", &[], "")),
+        ("/missing.h", response("404 Not Found", &[], ""))
+    ]);
+    run(&exec_in(&dir, &["--head-only-validate", "-q", "--url", &format!("{}/glcorearb.h", url)])).unwrap();
+    assert!(requests.recv().unwrap().starts_with("HEAD /glcorearb.h "));
+    assert!(!dir.join("include").exists() && !dir.join("src").exists());
+
+    let missing = format!("{}/missing.h", url);
+    let (stage, e) = run(&exec_in(&dir, &["--head-only-validate", "--url", &missing])).unwrap_err();
+    assert_eq!(stage, "fetch");
+    assert_eq!(e.to_string(), format!("failed to fetch {}: 404 Not Found", missing));
+}
+
+#[test]
+fn load_bitmap_records_each_function() {
+    let header = header_with(&["--load-bitmap"]);
+    assert!(header.contains("enum gl3w_proc_index {\n\tGL3W_PROC_BINDBUFFER,\n"));
+    assert!(header.contains("\tGL3W_PROC_VIEWPORT,\n\tGL3W_PROC_COUNT\n};\n"));
+    assert!(header.contains("int gl3wProcLoaded(int index);\n"));
+
+    let source = source_with(&["--load-bitmap"]);
+    assert!(source.contains(include_str!("../template/gl3w.load_bitmap.c")));
+    assert!(source.contains("    gl3wClear = (PFNGLCLEARPROC) get_proc(\"glClear\");\n    \
+                             set_loaded(GL3W_PROC_CLEAR, (GL3WglProc) gl3wClear);\n"));
+    assert!(!header_with(&[]).contains("gl3w_proc_index"));
+}
+
+#[test]
+fn patch_adds_a_prototype() {
+    let dir = temp_dir("patch");
+    let patch = fixture(&dir, "local.patch", "\
+--- a/glcorearb.h
++++ b/glcorearb.h
+@@ -149,4 +149,5 @@
+ #ifdef GL_GLEXT_PROTOTYPES
+ GLAPI void APIENTRY glTextureBarrierNV (void);
++GLAPI void APIENTRY glLocalThingEXT (GLuint thing);
+ #endif
+ #endif /* GL_NV_texture_barrier */
+");
+    let exec = exec_in(&dir, &["--patch", &patch]);
+    cache(&exec, GLCOREARB_H);
+    let procs = exec.gen_procs(&exec.get_glcorearb_h().unwrap());
+    let local = procs.iter().find(|p| p.id == "glLocalThingEXT").unwrap();
+    assert_eq!(local.block, Some("GL_NV_texture_barrier".to_string()));
+    assert_eq!(procs.len(), 21);
+
+    // A patch for another header fails rather than being half applied
+    cache(&exec, VENDOR_H);
+    let e = exec.get_glcorearb_h().unwrap_err();
+    assert!(e.to_string().starts_with(&format!("failed to apply {}: ", patch)));
+}
+
+#[test]
+fn calling_convention_is_used_for_the_pointers() {
+    let args = ["--calling-convention", "GLAPIENTRY"];
+    let header = header_with(&args);
+    assert!(header.contains("typedef void (GLAPIENTRY *GL3W_PFNGLCLEARPROC) (GLbitfield mask);\n"));
+    assert_eq!(line_with(&header, " gl3wClear;"), ["extern", "GL3W_PFNGLCLEARPROC", "gl3wClear;"]);
+    let source = source_with(&args);
+    assert!(source.contains(r#"gl3wClear = (GL3W_PFNGLCLEARPROC) get_proc("glClear");"#));
+    assert_eq!(exec_err(&["--calling-convention", "__stdcall x"]), "invalid --calling-convention `__stdcall x`");
+}
+
+#[test]
+fn audit_reports_drift_from_the_registry() {
+    let dir = temp_dir("audit");
+    // Generated from an older registry with glOldThing and without
+    // glClipControl, with different options
+    let stale = header_with(&["--align", "0"]).replace("#define glClipControl gl3wClipControl\n",
+                                                       "#define glOldThing gl3wOldThing\n");
+    let path = fixture(&dir, "gl3w.h", &stale);
+
+    let audit = exec(&["audit", "--header", &path]);
+    let (audited, removed, added) = audit.audit(&audit.gen_procs(GLCOREARB_H)).unwrap();
+    assert_eq!(audited, PathBuf::from(&path));
+    assert_eq!(removed, ["glOldThing"]);
+    assert_eq!(added, ["glClipControl"]);
+
+    let path = fixture(&dir, "empty.h", "/* nothing */\n");
+    assert!(exec(&["audit", "--header", &path]).audit(&[]).is_err());
+}
+
+#[test]
+fn upstream_layout_matches_gl3w() {
+    let exec = exec(&["--layout", "upstream"]);
+    match exec.path_gl3w {
+        Gl3wPath::Separate(ref path_h, ref path_c) => {
+            assert_eq!(path_h, Path::new("include/GL/gl3w.h"));
+            assert_eq!(path_c, Path::new("src/gl3w.c"));
+        }
+        Gl3wPath::Single(_) => unreachable!()
+    }
+    assert_eq!(exec.path_glcorearb, PathBuf::from("include/GL/glcorearb.h"));
+    assert_eq!(exec_err(&["--layout", "flat"]), "unknown layout `flat`");
+}
+
+#[test]
+fn trace_wraps_each_function_by_name() {
+    let header = header_with(&["--trace", "--align", "0"]);
+    let traced = &header[header.find("#ifdef GL3W_TRACE\n").unwrap()..header.find("#else\n").unwrap()];
+    assert!(traced.contains("static inline void gl3w_trace_glClear(GLbitfield mask) \
+                             { GL3W_TRACE_CALL(\"glClear\"); gl3wClear(mask); }\n\
+                             #define glClear gl3w_trace_glClear\n"));
+    assert!(traced.contains("static inline GLsync gl3w_trace_glFenceSync(GLenum condition, GLbitfield flags) \
+                             { GL3W_TRACE_CALL(\"glFenceSync\"); return gl3wFenceSync(condition, flags); }\n\
+                             #define glFenceSync gl3w_trace_glFenceSync\n"));
+    assert!(!header_with(&[]).contains("gl3w_trace_"));
+}
+
+#[test]
+fn max_size_stops_large_downloads() {
+    let dir = temp_dir("max-size");
+    let body = "x".repeat(1000);
+    // One honest about its length, one sending no length at all
+    let no_length = format!("HTTP/1.1 200 OK\r\nConnection: close\r\n\r\n{}", body);
+    let (url, _) = serve(vec![
+        ("/sized.h", response("200 OK", &[], &body)),
+        ("/unsized.h", no_length)
+    ]);
+    for name in &["sized.h", "unsized.h"] {
+        let url = format!("{}/{}", url, name);
+        let exec = exec_in(&dir, &["--url", &url, "--max-size", "100", "--no-cache"]);
+        let err = exec.get_glcorearb_h().unwrap_err();
+        assert_eq!(err.to_string(), format!("{} is larger than the maximum size of 100 bytes; use --max-size to allow it", url));
+        assert!(!exec.path_glcorearb.exists());
+    }
+}
+
+#[test]
+fn export_macro_prefixes_the_declarations() {
+    let header = header_with(&["--export-macro", "MYLIB_API", "--align", "0"]);
+    let block = include_str!("../template/gl3w.export.h").replace("GL3W_API", "MYLIB_API");
+    assert!(header.contains(&block));
+    assert!(header.contains("#ifdef GL3W_BUILD\n#define MYLIB_API __declspec(dllexport)\n"));
+    assert!(header.contains("\nMYLIB_API int gl3wInit(void);\n"));
+    assert!(header.contains("\nextern MYLIB_API PFNGLCLEARPROC gl3wClear;\n"));
+    assert!(header.find(&block).unwrap() < header.find("gl3wInit(void)").unwrap());
+    assert!(!header_with(&[]).contains("GL3W_BUILD"));
+}
+
+#[test]
+fn manifest_formats_list_the_source_and_functions() {
+    let dir = temp_dir("manifest-formats");
+    let url = "https://example.com/gl/glcorearb.h";
+    let expected = [
+        ("text", format!("source {}\n", url), "\nglClear\n"),
+        ("json", format!("  \"source\": \"{}\",\n", url), "\n    \"glClear\",\n"),
+        ("toml", format!("source = \"{}\"\n", url), "\n    \"glClear\",\n")
+    ];
+    for &(format, ref source, clear) in &expected {
+        let exec = exec(&["--url", url, "--output-manifest-format", format]);
+        let mut out = Vec::new();
+        gen_manifest(&mut out, &exec, &exec.gen_procs(GLCOREARB_H)).unwrap();
+        let manifest = String::from_utf8(out).unwrap();
+        assert!(manifest.contains(source), "{}", format);
+        assert!(manifest.contains(clear), "{}", format);
+        // Each reads back as the same functions
+        let path = fixture(&dir, format, &manifest);
+        assert_eq!(read_manifest(Path::new(&path)).unwrap().unwrap(), ids(&exec));
+    }
+    assert_eq!(exec_err(&["--output-manifest-format", "yaml"]), "unknown manifest format `yaml`");
+}
+
+#[test]
+fn index_enum_follows_the_declarations() {
+    let args = ["--index-enum"];
+    let header = header_with(&args);
+    let start = header.find("enum gl3w_proc_index {\n").unwrap();
+    let body = &header[start..header[start..].find("};\n").unwrap() + start];
+    let indices = body.lines().skip(1).map(|l| l.trim().trim_matches(',').to_string()).collect::<Vec<_>>();
+    let mut expected = ids(&exec(&args)).iter().map(|id| format!("GL3W_PROC_{}", id[2..].to_uppercase())).collect::<Vec<_>>();
+    expected.push("GL3W_PROC_COUNT".to_string());
+    assert_eq!(indices, expected);
+    assert!(!header.contains("gl3wProcLoaded"));
+}
+
+#[test]
+fn response_files_expand_in_place() {
+    let dir = temp_dir("response-files");
+    let nested = fixture(&dir, "nested.rsp", "--align 0\n");
+    let rsp = fixture(&dir, "gl3w.rsp", &format!("--exclude-match glViewport\n\
+                                                  --exclude-match \"glClear$\" --prefix gl3w_\n@{}\n", nested));
+    let args = vec!["--no-timestamp".to_string(), format!("@{}", rsp), "-q".to_string()];
+    let direct = ["--no-timestamp", "--exclude-match", "glViewport", "--exclude-match", "glClear$",
+                  "--prefix", "gl3w_", "--align", "0", "-q"];
+    let expanded = expand_response_files(args, 0).unwrap();
+    assert_eq!(expanded, direct);
+    let from_file = parse_args(expanded.into_iter()).unwrap();
+    assert_eq!(ids(&from_file), ids(&exec(&direct)));
+    assert!(!ids(&from_file).contains(&"glClear".to_string()) && ids(&from_file).contains(&"glClearColor".to_string()));
+
+    let looped = fixture(&dir, "loop.rsp", "");
+    fixture(&dir, "loop.rsp", &format!("@{}", looped));
+    assert_eq!(expand_response_files(vec![format!("@{}", looped)], 0).unwrap_err(), "response files nested too deeply");
+}
+
+#[test]
+fn extern_c_guards_the_declarations() {
+    let header = header_with(&[]);
+    let begin = header.find(EXTERN_C_BEGIN).unwrap();
+    let end = header.rfind(EXTERN_C_END).unwrap();
+    for decl in &["int gl3wInit(void);", " gl3wClear;", "#define glClear "] {
+        let at = header.find(decl).unwrap();
+        assert!(begin < at && at < end, "{}", decl);
+    }
+    assert_eq!(header.matches("extern \"C\"").count(), 1);
+
+    let header = header_with(&["--no-extern-c"]);
+    assert!(!header.contains("__cplusplus") && header.contains("int gl3wInit(void);"));
+}
+
+#[test]
+fn emit_loader_only_loads_the_declared_functions() {
+    let dir = temp_dir("emit-loader-only");
+    let header = fixture(&dir, "gl3w.h", &header_with(&["--match", "^glClear", "--match", "Buffers$"]));
+    // Nothing is listening, so any download would fail the run
+    let exec = exec_in(&dir, &["--emit-loader-only", "--header", &header, "--url", "http://127.0.0.1:1/glcorearb.h", "-q"]);
+    run(&exec).unwrap();
+    assert!(!exec.path_glcorearb.exists());
+
+    let source = read(dir.join("src/gl3w.c"));
+    let loaded = source.lines().filter_map(|l| l.find("get_proc(\"").map(|i| l[i + 10..].split('"').next().unwrap()))
+                       .collect::<Vec<_>>();
+    // glGetIntegerv is always declared, gl3wInit uses it
+    assert_eq!(loaded, ["glClear", "glClearColor", "glCreateBuffers", "glGenBuffers", "glGetIntegerv"]);
+}
+
+/// An observer recording the hooks called, in order.
+#[derive(Debug, Default)]
+struct Recorder(Mutex<Vec<String>>);
+
+impl Gl3wObserver for Recorder {
+    fn on_download_progress(&self, _url: &str, bytes: u64, total: Option<u64>) {
+        self.0.lock().unwrap().push(format!("progress {}/{}", bytes, total.unwrap()));
+    }
+
+    fn on_download_finished(&self, _url: &str, bytes: u64) {
+        self.0.lock().unwrap().push(format!("finished {}", bytes));
+    }
+
+    fn on_parsed(&self, count: usize) {
+        self.0.lock().unwrap().push(format!("parsed {}", count));
+    }
+
+    fn on_file_written(&self, path: &Path) {
+        self.0.lock().unwrap().push(format!("written {}", path.file_name().unwrap().to_str().unwrap()));
+    }
+}
+
+#[test]
+fn observer_hooks_fire_in_order() {
+    let dir = temp_dir("observer");
+    let (url, _) = serve(vec![("/glcorearb.h", response("200 OK", &[], GLCOREARB_H))]);
+    let mut exec = exec_in(&dir, &["--url", &format!("{}/glcorearb.h", url), "-q"]);
+    let recorder = Arc::new(Recorder::default());
+    exec.set_observer(recorder.clone());
+    run(&exec).unwrap();
+
+    let events = recorder.0.lock().unwrap().clone();
+    let len = GLCOREARB_H.len();
+    let count = ids(&exec).len();
+    let progress = events.iter().take_while(|e| e.starts_with("progress ")).count();
+    assert!(progress > 0);
+    assert_eq!(events[progress - 1], format!("progress {}/{}", len, len));
+    assert_eq!(&events[progress..], &[format!("finished {}", len), format!("parsed {}", count),
+                                      "written gl3w.h".to_string(), "written gl3w.c".to_string()]);
+}
+
+#[test]
+fn header_name_builds_the_registry_url() {
+    let glext = exec(&["--header-name", "glext.h"]);
+    assert_eq!(glext.url_glcorearb, "https://registry.khronos.org/OpenGL/api/GL/glext.h");
+    assert_eq!(glext.path_glcorearb, PathBuf::from("include/GL/glext.h"));
+
+    let egl = exec(&["--header-name", "egl.h", "--registry-base", "https://mirror.example.com/OpenGL/api/"]);
+    assert_eq!(egl.url_glcorearb, "https://mirror.example.com/EGL/api/EGL/egl.h");
+    assert_eq!(exec_err(&["--header-name", "gl4.h"]), "unknown registry header `gl4.h`");
+}
+
+#[test]
+fn annotate_source_names_each_header() {
+    let dir = temp_dir("annotate-source");
+    // Headers are told apart by their include guards, as the registry's are
+    let glext = format!("#ifndef __gl_glext_h_\n#define __gl_glext_h_ 1\n{}#endif\n", VENDOR_H);
+    let (url, _) = serve(vec![("/glext.h", response("200 OK", &[], &glext))]);
+    let exec = generate(&dir, &["--extra-header", &format!("{}/glext.h", url), "--annotate-source", "-q"]);
+    let (header, _) = generated(&exec);
+    assert_eq!(line_with(&header, " gl3wVendorThingEXT;")[3..], ["/*", "from", "glext.h", "*/"]);
+    assert_eq!(line_with(&header, " gl3wClear;")[3..], ["/*", "from", "glcorearb.h", "*/"]);
+    assert!(!header_with(&[]).contains("/* from "));
+}
+
+#[test]
+fn fail_on_warning_fails_the_run() {
+    let dir = temp_dir("fail-on-warning");
+    let names = fixture(&dir, "names.txt", "glNoSuchThing engineNoSuchThing\n");
+    let warned = generate(&dir, &["--name-map", &names, "-q"]);
+    assert_eq!(warned.warnings.load(Ordering::SeqCst), 1);
+
+    // The warnings of other runs do not count
+    generate(&dir, &["--fail-on-warning", "-q"]);
+
+    let strict = exec_in(&dir, &["--name-map", &names, "--fail-on-warning", "-q"]);
+    let (stage, e) = run(&strict).unwrap_err();
+    assert_eq!((stage, e.to_string()), ("generate", "1 warning given with --fail-on-warning".to_string()));
+}
+
+#[test]
+fn vendor_keeps_core_and_its_extensions() {
+    let nv = ids(&exec(&["--vendor", "NV"]));
+    assert!(nv.contains(&"glClear".to_string()) && nv.contains(&"glClipControl".to_string()));
+    assert!(nv.contains(&"glTextureBarrierNV".to_string()));
+    assert!(!nv.iter().any(|id| id.ends_with("ARB")));
+
+    let arb = ids(&exec(&["--vendor", "ARB"]));
+    assert!(arb.contains(&"glDebugMessageCallbackARB".to_string()) && !arb.contains(&"glTextureBarrierNV".to_string()));
+    assert_eq!(arb.len(), nv.len() + 1);
+}
+
+#[test]
+fn corrupt_cache_is_downloaded_again() {
+    let dir = temp_dir("corrupt-cache");
+    let (url, requests) = serve(vec![
+        ("/glcorearb.h", response("200 OK", &[], GLCOREARB_H)),
+        ("/glcorearb.h", response("200 OK", &[], GLCOREARB_H))
+    ]);
+    let exec = exec_in(&dir, &["--url", &format!("{}/glcorearb.h", url)]);
+    assert_eq!(exec.get_glcorearb_h().unwrap(), GLCOREARB_H);
+    assert!(read(checksum_path(&exec.path_glcorearb)).starts_with(&sha256::hex_digest(GLCOREARB_H.as_bytes())));
+    requests.recv().unwrap();
+
+    // An intact cache is used as it is
+    assert_eq!(exec.get_glcorearb_h().unwrap(), GLCOREARB_H);
+    assert!(requests.try_recv().is_err());
+
+    fixture(&dir, "include/GL/glcorearb.h", &GLCOREARB_H.replace("glClear", "glCleat"));
+    let corrupt = format!("cached {} does not match its checksum, downloading it again", exec.path_glcorearb.display());
+    assert_eq!(warnings(|| assert_eq!(exec.get_glcorearb_h().unwrap(), GLCOREARB_H)), vec![corrupt]);
+    requests.recv().unwrap();
+    assert_eq!(read(&exec.path_glcorearb), GLCOREARB_H);
+}
+
+#[test]
+fn error_enum_is_returned_by_gl3w_init() {
+    let header = header_with(&["--error-enum"]);
+    assert!(header.contains(include_str!("../template/gl3w.error.h")));
+    assert!(header.contains("\nenum gl3w_error gl3wInit(void);\n"));
+    assert!(header.find("enum gl3w_error {").unwrap() < header.find("gl3wInit(void)").unwrap());
+
+    let source = source_with(&["--error-enum"]);
+    assert!(source.contains("\nenum gl3w_error gl3wInit(void)\n{"));
+    assert!(source.contains("\t\treturn GL3W_ERROR_LIBRARY_OPEN;\n"));
+    assert!(source.contains("\t\treturn GL3W_ERROR_INIT;\n"));
+    assert!(source.contains("\t\treturn GL3W_ERROR_OPENGL_VERSION;\n\treturn GL3W_OK;\n"));
+    assert!(!source.contains("return -"));
+    assert!(!header_with(&[]).contains("gl3w_error"));
+}
+
+#[test]
+fn sort_case_insensitive_folds_case() {
+    let header = "\
+GLAPI void APIENTRY glFooZed (void);
+GLAPI void APIENTRY glFoobaz (void);
+GLAPI void APIENTRY glFooBar (void);
+GLAPI void APIENTRY glFoobar (void);
+GLAPI void APIENTRY glFooBar (void);
+";
+    let names = |args: &[&str]| exec(args).gen_procs(header).into_iter().map(|p| p.id).collect::<Vec<_>>();
+    assert_eq!(names(&[]), ["glFooBar", "glFooZed", "glFoobar", "glFoobaz"]);
+    // Duplicates are still merged, and names only differing in case kept
+    assert_eq!(names(&["--sort-case-insensitive"]), ["glFooBar", "glFoobar", "glFoobaz", "glFooZed"]);
+}
+
+#[test]
+fn cross_platform_includes_pick_each_platform_header() {
+    let header = header_with(&["--cross-platform-includes", "--profile", "compatibility"]);
+    assert!(header.contains("#if defined(_WIN32)\n"));
+    assert!(header.contains("#include <GL/gl.h>\n#elif defined(__APPLE__) || defined(__APPLE_CC__)\n\
+                             #include <OpenGL/gl.h>\n#else\n#include <GL/gl.h>\n#endif\n#include <GL/glext.h>\n"));
+    assert!(header.find("#include <windows.h>").unwrap() < header.find("#include <GL/glext.h>").unwrap());
+
+    let header = header_with(&["--cross-platform-includes"]);
+    assert!(header.contains("#include <windows.h>\n#endif\n#include <GL/glcorearb.h>\n"));
+    assert!(!header_with(&[]).contains("windows.h"));
+}
+
+#[test]
+fn minify_keeps_every_declaration() {
+    assert_eq!(minify("a  /* one\n two */ b\n\n\t#define X \"a  /* b */\" // x\nchar c = ' ';\n"),
+               "a b\n#define X \"a  /* b */\"\nchar c = ' ';\n");
+
+    let (header, source) = (header_with(&["--minify"]), source_with(&["--minify"]));
+    assert!(header.len() < header_with(&[]).len() && source.len() < source_with(&[]).len());
+    assert!(!header.contains("/*") && !header.contains("\n\n"));
+    for id in ids(&exec(&[])) {
+        let var = format!("gl3w{}", &id[2..]);
+        assert!(header.contains(&format!("\nextern PFN{}PROC {};\n", id.to_uppercase(), var)), "{}", id);
+        assert!(header.contains(&format!("\n#define {} {}\n", id, var)), "{}", id);
+        assert!(source.contains(&format!("get_proc(\"{}\")", id)), "{}", id);
+    }
+}
+
+#[test]
+fn khrplatform_include_rewrites_the_include() {
+    let glcorearb_h = GLCOREARB_H.replacen("#ifndef APIENTRY\n", "#include <KHR/khrplatform.h>\n\n#ifndef APIENTRY\n", 1);
+    let header_with = |khrplatform: &str| {
+        let exec = exec(&["--self-contained", "--khrplatform-include", khrplatform]);
+        exec.generate_header(&glcorearb_h, &exec.gen_procs(&glcorearb_h), None)
+    };
+    let header = header_with("vendor/khrplatform.h");
+    assert!(header.contains("\n#include \"vendor/khrplatform.h\"\n"));
+    assert!(!header.contains("<KHR/khrplatform.h>"));
+    assert!(header_with("<khr/platform.h>").contains("\n#include <khr/platform.h>\n"));
+    assert_eq!(exec_err(&["--khrplatform-include", "vendor/khrplatform.h"]),
+               "--khrplatform-include requires --self-contained or --dump-typedefs");
+}
+
+#[test]
+fn bench_times_each_stage() {
+    // The timings are printed, so the run is made by a child process
+    if env::var("GL3W_NATIVE_TEST_CHILD").is_ok() {
+        run(&exec(&["--input", "glcorearb.h", "--bench", "-q"])).unwrap();
+        return;
+    }
+
+    let dir = temp_dir("bench");
+    fixture(&dir, "glcorearb.h", GLCOREARB_H);
+    let output = run_child("bench_times_each_stage", &dir);
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let stages = stderr.lines().filter(|l| l.ends_with(" ms")).map(|l| l.split(':').next().unwrap()).collect::<Vec<_>>();
+    assert_eq!(stages, ["fetch", "parse", "generate"]);
+}
+
+#[test]
+fn allow_host_checks_every_url() {
+    let dir = temp_dir("allow-host");
+    let (url, requests) = serve(vec![
+        ("/glcorearb.h", response("200 OK", &[], GLCOREARB_H)),
+        ("/moved.h", response("302 Found", &["Location: http://localhost:1/glcorearb.h"], ""))
+    ]);
+    let allowed = exec_in(&dir, &["--url", &format!("{}/glcorearb.h", url), "--allow-host", "127.0.0.1", "--no-write-glcorearb"]);
+    assert_eq!(allowed.get_glcorearb_h().unwrap(), GLCOREARB_H);
+    requests.recv().unwrap();
+
+    // Nothing is sent to a host which is not allowed, even on a redirect
+    let other = exec_in(&dir, &["--url", &format!("{}/glcorearb.h", url), "--allow-host", "registry.khronos.org"]);
+    let e = other.get_glcorearb_h().unwrap_err();
+    assert!(e.to_string().ends_with("127.0.0.1 is not an allowed host; allow it with --allow-host"), "{}", e);
+    assert!(requests.try_recv().is_err());
+
+    let redirected = exec_in(&dir, &["--url", &format!("{}/moved.h", url), "--allow-host", "127.0.0.1"]);
+    let e = redirected.get_glcorearb_h().unwrap_err();
+    assert!(e.to_string().ends_with("localhost is not an allowed host; allow it with --allow-host"), "{}", e);
+    assert!(requests.recv().unwrap().starts_with("GET /moved.h "));
+}
+
+#[test]
+fn selftest_counts_the_resolved_functions() {
+    let header = header_with(&["--emit-selftest"]);
+    assert!(header.contains("\ndouble gl3wSelfTest(void);\n"));
+    // It is counted by the load bitmap, which is enabled for it
+    assert!(header.contains("\tGL3W_PROC_COUNT\n};\n") && header.contains("int gl3wProcLoaded(int index);\n"));
+
+    let source = source_with(&["--emit-selftest"]);
+    assert!(source.contains(include_str!("../template/gl3w.selftest.c")));
+    assert!(source.contains("resolved += gl3wProcLoaded(i);") && source.contains("return (double) resolved / GL3W_PROC_COUNT;"));
+    assert!(!source_with(&[]).contains("gl3wSelfTest"));
+}
+
+#[test]
+fn lazy_extensions_load_separately() {
+    let source = source_with(&["--lazy-extensions"]);
+    let body = |name: &str| {
+        let start = source.find(name).unwrap();
+        source[start..start + source[start..].find("\n}\n").unwrap()].to_string()
+    };
+    let core = body("static void load_procs(void)\n{");
+    let extensions = body("static int load_extension_procs(const char *ext)\n{");
+    assert!(core.contains("get_proc(\"glClear\")") && !extensions.contains("get_proc(\"glClear\")"));
+    for id in &["glDebugMessageCallbackARB", "glDebugMessageControlARB", "glTextureBarrierNV"] {
+        let load = format!("get_proc(\"{}\")", id);
+        assert!(extensions.contains(&load) && !core.contains(&load), "{}", id);
+    }
+    assert!(extensions.contains("    if (!strcmp(ext, \"GL_NV_texture_barrier\")) {\n        gl3wTextureBarrierNV = "));
+    assert!(header_with(&["--lazy-extensions"]).contains("\nint gl3wLoadExtension(const char *ext);\n"));
+}
+
+#[test]
+fn insecure_only_skips_verification_when_given() {
+    assert!(!exec(&[]).client.insecure);
+    assert!(!exec(&["--allow-host", "registry.khronos.org", "--no-network"]).client.insecure);
+    assert!(exec(&["--insecure"]).client.insecure);
+    // Warned about like any other warning, so --fail-on-warning counts it
+    assert_eq!(exec(&["--insecure", "-q"]).warnings.load(Ordering::SeqCst), 1);
+    assert!(Http::new(Vec::new(), true, false).insecure && !Http::new(Vec::new(), false, false).insecure);
+}
+
+#[test]
+fn depfile_lists_outputs_and_inputs() {
+    let dir = temp_dir("depfile");
+    let depfile = dir.join("gl3w.d");
+    let exec = generate(&dir, &["--depfile", depfile.to_str().unwrap(), "-q"]);
+    let (h, c) = (dir.join("src/gl3w.h"), dir.join("src/gl3w.c"));
+    assert_eq!(read(&depfile), format!("{} {}: \\\n  {}\n\n{}:\n", h.display(), c.display(),
+                                       exec.path_glcorearb.display(), exec.path_glcorearb.display()));
+
+    let mut out = Vec::new();
+    gen_depfile(&mut out, &[PathBuf::from("my gl3w.h")], &[PathBuf::from("a$b#c.h")]).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "my\\ gl3w.h: \\\n  a$$b\\#c.h\n\na$$b\\#c.h:\n");
+}
+
+#[test]
+fn if_changed_skips_an_unchanged_header() {
+    // What each run reports is printed, so the runs are made by a child
+    if env::var("GL3W_NATIVE_TEST_CHILD").is_ok() {
+        let exec = exec(&["--input", "glcorearb.h", "--if-changed"]);
+        run(&exec).unwrap();
+        // Anything the second run wrote would replace this
+        fixture(Path::new("."), "src/gl3w.h", "edited\n");
+        run(&exec).unwrap();
+        println!("after: {}", read("src/gl3w.h"));
+        return;
+    }
+
+    let dir = temp_dir("if-changed");
+    fixture(&dir, "glcorearb.h", GLCOREARB_H);
+    let output = run_child("if_changed_skips_an_unchanged_header", &dir);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("generated "), "{}", stdout);
+    assert!(stdout.contains("\nglcorearb.h unchanged, nothing generated\nafter: edited\n"), "{}", stdout);
+    assert_eq!(read(dir.join("src/.gl3w-native-state")).split_whitespace().next().unwrap().len(), 64);
+}
+
+#[test]
+fn pragma_once_replaces_the_include_guard() {
+    let header = header_with(&["--pragma-once"]);
+    assert!(header.contains("\n#pragma once\n"));
+    assert!(!header.contains("__gl3w_h_"));
+    // The guard's `#endif` went with it
+    let count = |directive: &str| header.lines().filter(|l| l.starts_with(directive)).count();
+    assert_eq!(count("#if"), count("#endif"));
+
+    let header = header_with(&[]);
+    assert!(header.contains("#ifndef __gl3w_h_\n#define __gl3w_h_\n") && !header.contains("#pragma once"));
+}
+
+#[test]
+fn attributed_prototypes_are_parsed() {
+    let header = "\
+__attribute__((visibility(\"default\"))) GLAPI void APIENTRY glVisibleThing (GLuint thing);
+GLAPI __attribute__((deprecated)) const GLubyte *GLAPIENTRY glOldThing (GLenum name);
+__declspec(dllimport) GLAPI GLenum GLAPIENTRY glImportedThing (void);
+";
+    let procs = exec(&[]).gen_procs(header);
+    let protos = procs.iter().map(|p| (p.id.as_str(), p.proto.clone().unwrap())).collect::<Vec<_>>();
+    assert_eq!(protos, [
+        ("glImportedThing", ("GLenum".to_string(), "void".to_string())),
+        ("glOldThing", ("const GLubyte *".to_string(), "GLenum name".to_string())),
+        ("glVisibleThing", ("void".to_string(), "GLuint thing".to_string()))
+    ]);
+}
+
+#[test]
+fn macos_loader_uses_the_framework() {
+    let source = source_with(&["--loader", "macos"]);
+    assert!(source.contains("dlopen(\"/System/Library/Frameworks/OpenGL.framework/OpenGL\", RTLD_LAZY | RTLD_LOCAL)"));
+    assert!(source.contains("return (GL3WglProc) dlsym(libgl, proc);"));
+    assert!(!source.contains("glXGetProcAddress") && !source.contains("wglGetProcAddress"));
+}
+
+#[test]
+fn group_by_version_sections_the_declarations() {
+    let header = header_with(&["--group-by-version", "--align", "0"]);
+    assert!(header.contains("/* OpenGL 3.2 */\n\
+                             extern PFNGLCLIENTWAITSYNCPROC gl3wClientWaitSync;\n\
+                             extern PFNGLFENCESYNCPROC gl3wFenceSync;\n\n\
+                             /* OpenGL 3.3 */\n\
+                             extern PFNGLVERTEXATTRIBDIVISORPROC gl3wVertexAttribDivisor;\n\n"));
+    assert!(header.contains("/* GL_NV_texture_barrier */\nextern PFNGLTEXTUREBARRIERNVPROC gl3wTextureBarrierNV;\n"));
+    // Versions come in order, each only once
+    let sections = header.lines().filter(|l| l.starts_with("/* OpenGL ") && l.ends_with(" */")).collect::<Vec<_>>();
+    assert_eq!(sections, ["/* OpenGL functions */", "/* OpenGL 1.0 */", "/* OpenGL 1.5 */", "/* OpenGL 3.0 */",
+                          "/* OpenGL 3.2 */", "/* OpenGL 3.3 */", "/* OpenGL 4.0 */", "/* OpenGL 4.5 */"]);
+}
+
+#[test]
+fn no_network_fails_before_connecting() {
+    let dir = temp_dir("no-network");
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/glcorearb.h", listener.local_addr().unwrap());
+    let exec = exec_in(&dir, &["--url", &url, "--no-network", "--extra-header", &url]);
+    let e = exec.get_glcorearb_h().unwrap_err();
+    assert_eq!(e.to_string(), format!("failed to fetch {}: network access is disabled by --no-network; \
+                                       use --input or populate the cache first", url));
+    listener.set_nonblocking(true).unwrap();
+    assert_eq!(listener.accept().unwrap_err().kind(), io::ErrorKind::WouldBlock);
+
+    // The cache is still used
+    cache(&exec, GLCOREARB_H);
+    assert_eq!(exec_in(&dir, &["--url", &url, "--no-network"]).get_glcorearb_h().unwrap(), GLCOREARB_H);
+    assert_eq!(exec_err(&["--no-network", "--no-cache"]), "--no-network cannot be used with --no-cache");
+}
+
+#[test]
+fn refcount_init_pairs_init_and_shutdown() {
+    let source = source_with(&["--refcount-init"]);
+    assert!(source.contains(include_str!("../template/gl3w.refcount.c")));
+    assert!(source.contains("\tif (refcount > 0) {\n\t\trefcount++;\n\t\treturn 0;\n\t}\n"));
+    assert!(source.contains("\tif (refcount == 0 || --refcount > 0)\n\t\treturn;\n\treset_procs();\n"));
+    // Every pointer is reset by the last shutdown
+    let start = source.find("\nstatic void reset_procs(void)\n{\n").unwrap();
+    let reset = &source[start..start + source[start..].find("}\n").unwrap()];
+    for p in exec(&[]).gen_procs(GLCOREARB_H) {
+        assert!(reset.contains(&format!("    {} = NULL;\n", p.var)), "{}", p.var);
+    }
+    assert!(header_with(&["--refcount-init"]).contains("\nvoid gl3wShutdown(void);\n"));
+    assert_eq!(exec_err(&["--refcount-init", "--idempotent-init"]), "--refcount-init cannot be used with --idempotent-init");
+}
+
+#[test]
+fn proc_names_follow_the_index_enum() {
+    let between = |text: &str, start: &str, end: &str| {
+        let at = text.find(start).unwrap() + start.len();
+        text[at..at + text[at..].find(end).unwrap()].lines().map(|l| l.trim().to_string()).collect::<Vec<_>>()
+    };
+    let header = header_with(&["--proc-names"]);
+    assert!(header.contains("\nextern const char *const gl3w_proc_names[GL3W_PROC_COUNT];\n"));
+    let indices = between(&header, "enum gl3w_proc_index {\n", "\tGL3W_PROC_COUNT\n");
+
+    let source = source_with(&["--proc-names"]);
+    let names = between(&source, "const char *const gl3w_proc_names[GL3W_PROC_COUNT] = {\n", "};\n");
+    assert_eq!(names.len(), indices.len());
+    for (name, index) in names.iter().zip(&indices) {
+        assert_eq!(format!("GL3W_PROC_{},", name.trim_matches(|c| c == '"' || c == ',')[2..].to_uppercase()), *index);
+    }
+    let ids = ids(&exec(&[])).iter().map(|id| format!("\"{}\",", id)).collect::<Vec<_>>();
+    assert_eq!(names, ids);
+}
+
+#[test]
+fn empty_download_is_an_error() {
+    let dir = temp_dir("empty-download");
+    let (url, _) = serve(vec![
+        ("/empty.h", response("200 OK", &[], "")),
+        ("/blank.h", response("200 OK", &[], " \n\n")),
+        ("/text.h", response("200 OK", &[], "Service temporarily unavailable\n"))
+    ]);
+    for &(name, message) in &[("empty.h", "returned an empty body"), ("blank.h", "returned an empty body"),
+                              ("text.h", "returned 32 bytes without a single preprocessor line, which is not a header")] {
+        let url = format!("{}/{}", url, name);
+        let exec = exec_in(&dir, &["--url", &url]);
+        assert_eq!(exec.get_glcorearb_h().unwrap_err().to_string(), format!("{} {}", url, message));
+        // Nothing was cached to be generated from next time
+        assert!(!exec.path_glcorearb.exists());
+    }
+}
+
+#[test]
+fn header_include_rewrites_the_source_include() {
+    let source = source_with(&["--header-include", "../include/gl3w.h"]);
+    assert!(source.contains("\n#include \"../include/gl3w.h\"\n"));
+    assert!(!source.contains("#include <GL/gl3w.h>"));
+    assert!(source_with(&["--header-include", "<gl3w/gl3w.h>"]).contains("\n#include <gl3w/gl3w.h>\n"));
+    assert!(source_with(&[]).contains("\n#include <GL/gl3w.h>\n"));
+    assert_eq!(exec_err(&["--header-include", "gl3w.h", "--self-contained"]),
+               "--header-include cannot be used with a single file");
+}
+
+#[test]
+fn cmake_package_defines_an_imported_target() {
+    let dir = temp_dir("cmake-package");
+    let package = dir.join("pkg");
+    let exec = generate(&dir, &["--cmake-package", package.to_str().unwrap(), "-q"]);
+    let (header, source) = generated(&exec);
+    assert_eq!(read(package.join("include/GL/gl3w.h")), header);
+    assert_eq!(read(package.join("src/gl3w.c")), source);
+    assert_eq!(read(package.join("include/GL/glcorearb.h")), GLCOREARB_H);
+
+    let config = read(package.join("gl3wConfig.cmake"));
+    assert!(config.contains("add_library(gl3w STATIC\n  \"${CMAKE_CURRENT_LIST_DIR}/src/gl3w.c\"\n)\n"));
+    assert!(config.contains("target_include_directories(gl3w PUBLIC \"${CMAKE_CURRENT_LIST_DIR}/include\")\n"));
+    assert!(config.contains("add_library(gl3w::gl3w ALIAS gl3w)\n"));
+}
+
+#[test]
+fn single_header_defines_before_use() {
+    let exec = exec(&["--self-contained"]);
+    let procs = exec.gen_procs(GLCOREARB_H);
+    let single = exec.generate_single(GLCOREARB_H, &procs, exec.detected_version(GLCOREARB_H));
+    let implementation = single.find("#if defined(GL3W_IMPLEMENTATION)").unwrap();
+    assert!(single.find("typedef unsigned int GLbitfield;").unwrap() < single.find("PFNGLCLEARPROC").unwrap());
+    for p in &procs {
+        let typedef = single.find(&format!("(APIENTRYP {})", p.pfn)).unwrap();
+        let declared = single.find(&format!(" {};", p.var)).unwrap();
+        let loaded = single.find(&format!("{} = ({})", p.var, p.pfn)).unwrap();
+        assert!(typedef < declared && declared < implementation && implementation < loaded, "{}", p.id);
+    }
+    // The API is declared before the implementation defines it
+    let init = single.find("\nint gl3wInit(void)\n{").unwrap();
+    assert!(single.find("int gl3wInit(void);").unwrap() < implementation && implementation < init);
+}
+
+#[test]
+fn registry_base_moves_every_header_url() {
+    let base = "https://mirror.example.com/khronos";
+    let core = exec(&["--registry-base", base]);
+    assert_eq!(core.url_glcorearb, "https://mirror.example.com/khronos/OpenGL/api/GL/glcorearb.h");
+    assert_eq!(core.url_glext, "https://mirror.example.com/khronos/OpenGL/api/GL/glext.h");
+    let gles = exec(&["--registry-base", "https://mirror.example.com/khronos/OpenGL/api", "--profile", "gles3"]);
+    assert_eq!(gles.url_glcorearb, "https://mirror.example.com/khronos/OpenGL/api/GLES3/gl3.h");
+    assert_eq!(exec_err(&["--registry-base", base, "--url", "http://example.com/glcorearb.h"]),
+               "--registry-base cannot be used with --url");
+}
+
+#[test]
+fn list_filtered_names_the_excluded_functions() {
+    let dir = temp_dir("list-filtered");
+    let list = dir.join("filtered.txt");
+    let exec = generate(&dir, &["--since", "4.0", "--list-filtered", list.to_str().unwrap(), "-q"]);
+    let kept = ids(&exec);
+    assert_eq!(kept, ["glClipControl", "glCreateBuffers", "glGetIntegerv", "glMinSampleShading"]);
+
+    let filtered = ids(&self::exec(&[])).into_iter().filter(|id| !kept.contains(id)).collect::<Vec<_>>();
+    assert_eq!(read(&list).lines().collect::<Vec<_>>(), filtered);
+    assert!(filtered.contains(&"glTextureBarrierNV".to_string()) && filtered.contains(&"glVertexAttribDivisor".to_string()));
+}
+
+#[test]
+fn cpp_loader_wraps_init_in_raii() {
+    let dir = temp_dir("lang-cpp");
+    let exec = generate(&dir, &["--lang", "cpp", "-q"]);
+    assert!(!dir.join("src/gl3w.h").exists() && !dir.join("src/gl3w.c").exists());
+    let (header, source) = (read(dir.join("src/gl3w.hpp")), read(dir.join("src/gl3w.cpp")));
+    assert_eq!((header.clone(), source.clone()), generated(&exec));
+
+    let loader = include_str!("../template/gl3w.loader.hpp");
+    assert!(header.contains(loader));
+    assert!(header.contains("\tLoader() : res(gl3wInit()) {}\n") && header.contains("\t\t\tgl3wShutdown();\n"));
+    // The C function pointers are still there, which the destructor resets
+    assert!(header.contains(" gl3wClear;\n") && header.contains("#define glClear "));
+    assert!(header.find(" gl3wClear;\n").unwrap() < header.find(loader).unwrap());
+    assert!(source.contains("void gl3wShutdown(void)\n{") && source.contains("    gl3wClear = NULL;\n"));
+}
+
+#[test]
+fn procs_schema_requires_each_property() {
+    let dir = temp_dir("procs-schema");
+    let schema = dir.join("procs.schema.json");
+    generate(&dir, &["--json-procs-schema", schema.to_str().unwrap(), "-q"]);
+
+    let schema = read(&schema);
+    assert!(schema.contains(r#""required": ["id", "var", "pfn"],"#));
+    assert!(schema.contains(r#""additionalProperties": false"#));
+    for name in &["id", "var", "pfn"] {
+        assert!(schema.contains(&format!("      \"{}\": {{ \"type\": \"string\", ", name)));
+    }
+    assert!(schema.contains(r#""pattern": "^PFN[A-Z0-9_]+PROC$""#));
+}
+
+#[test]
+fn resume_only_writes_the_missing_files() {
+    let dir = temp_dir("resume");
+    let exec = exec_in(&dir, &["--resume", "--pch-header", "--no-timestamp", "-q"]);
+    cache(&exec, GLCOREARB_H);
+    let (path_h, path_c) = match exec.path_gl3w {
+        Gl3wPath::Separate(ref path_h, ref path_c) => (path_h.clone(), path_c.clone()),
+        Gl3wPath::Single(_) => panic!("expected separate files")
+    };
+    let path_pch = exec.path_pch_header().unwrap();
+    let path_resume = path_h.with_file_name(".gl3w-native-resume");
+
+    // gl3w.c cannot be created over a directory, failing after gl3w.h and
+    // the precompiled header
+    fs::create_dir_all(&path_c).unwrap();
+    assert!(run(&exec).is_err());
+    let record = read(&path_resume);
+    let recorded = record.lines().map(|line| line.splitn(2, "  ").nth(1).unwrap()).collect::<Vec<_>>();
+    assert_eq!(recorded, [path_h.to_str().unwrap(), path_pch.to_str().unwrap()]);
+
+    fs::remove_dir(&path_c).unwrap();
+    let modified = |path: &Path| fs::metadata(path).unwrap().modified().unwrap();
+    let (modified_h, modified_pch) = (modified(&path_h), modified(&path_pch));
+    run(&exec).unwrap();
+    assert_eq!((modified(&path_h), modified(&path_pch)), (modified_h, modified_pch));
+    assert!(read(&path_c).contains(r#"get_proc("glClear")"#));
+    assert!(!path_resume.exists());
+
+    // An unchanged precompiled header is kept on every run
+    run(&exec).unwrap();
+    assert_eq!(modified(&path_pch), modified_pch);
+}
+
+#[test]
+fn init_groups_checks_each_group_before_loading() {
+    let source = source_with(&["--init-groups"]);
+    assert!(source.contains("static void load_procs(void)\n{\n\tload_procs_groups(GL3W_GROUP_ALL);\n}\n"));
+    assert!(source.contains("int gl3wInitGroups(unsigned groups)\n{\n\topen_libgl();\n\tload_procs_groups(groups);\n"));
+
+    let body = &source[source.find("static void load_procs_groups(unsigned groups)\n{").unwrap()..];
+    let body = &body[..body.find("\n}\n").unwrap()];
+    let loaded_in = |name: &str| {
+        let at = body.find(&format!("get_proc(\"{}\")", name)).unwrap();
+        body[..at].rfind("if (groups & ").map(|i| line_with(&body[i..], "if (groups & ")[3].to_string())
+    };
+    // gl3wInit needs glGetIntegerv to parse the version, whatever the groups
+    assert_eq!(loaded_in("glGetIntegerv"), None);
+    assert_eq!(loaded_in("glClear"), Some("GL3W_GROUP_CORE)".to_string()));
+    assert_eq!(loaded_in("glDebugMessageControlARB"), Some("GL3W_GROUP_ARB)".to_string()));
+    assert_eq!(loaded_in("glTextureBarrierNV"), Some("GL3W_GROUP_VENDOR)".to_string()));
+}
+
+#[test]
+fn dump_templates_writes_the_built_in_templates() {
+    let dir = temp_dir("dump-templates").join("templates");
+    run(&exec(&["--dump-templates", dir.to_str().unwrap(), "-q"])).unwrap();
+
+    for &(name, contents) in TEMPLATES {
+        assert_eq!(read(dir.join(name)), contents, "{}", name);
+    }
+    assert_eq!(read(dir.join("gl3w.preamble.c")), include_str!("../template/gl3w.preamble.c"));
+    assert_eq!(read(dir.join("gl3w.footer.h")), include_str!("../template/gl3w.footer.h"));
+}
+
+#[test]
+fn static_define_switches_the_storage() {
+    let header = header_with(&["--static-define"]);
+    assert!(header.contains("#if defined(GL3W_STATIC)\n#define GL3W_STORAGE static\n#else\n#define GL3W_STORAGE extern\n#endif\n"));
+    let clear = line_with(&header, " gl3wClear;");
+    assert_eq!(clear, ["GL3W_STORAGE", "PFNGLCLEARPROC", "gl3wClear;"]);
+    assert!(header.find("#if defined(GL3W_STATIC)").unwrap() < header.find(" gl3wClear;").unwrap());
+    assert!(!header.contains("extern PFNGLCLEARPROC"));
+
+    // The definitions are only needed when the header does not make them
+    let source = source_with(&["--static-define"]);
+    let guarded = &source[source.find("#if !defined(GL3W_STATIC)\n").unwrap()..];
+    assert!(guarded[..guarded.find("#endif\n").unwrap()].contains(" gl3wClear;\n"));
+}
+
+#[test]
+fn expect_count_checks_the_number_of_functions() {
+    let count = ids(&exec(&[])).len().to_string();
+    let dir = temp_dir("expect-count");
+    generate(&dir, &["--expect-count", &count, "-q"]);
+
+    let dir = temp_dir("expect-count-mismatch");
+    let exec = exec_in(&dir, &["--expect-count", "1000", "-q"]);
+    cache(&exec, GLCOREARB_H);
+    let (stage, e) = run(&exec).unwrap_err();
+    assert_eq!(stage, "verify");
+    assert_eq!(e.to_string(), format!("{} functions found in {}, expected 1000", count, exec.source()));
+    assert!(!dir.join("src/gl3w.h").exists());
+
+    assert_eq!(exec_err(&["--expect-count", "many"]), "invalid --expect-count `many`");
+}
+
+#[test]
+fn emscripten_loader_uses_emscripten_get_proc_address() {
+    assert!(exec(&["--loader", "emscripten"]).profile == Profile::Gles3);
+
+    let source = source_with(&["--loader", "emscripten"]);
+    assert!(source.contains(include_str!("../template/loader/emscripten.c")));
+    assert!(source.contains("#include <emscripten.h>\n"));
+    assert!(source.contains("\treturn (GL3WglProc) emscripten_GetProcAddress(proc);\n"));
+    assert!(!source.contains("dlopen") && !source.contains("wglGetProcAddress"));
+    assert!(header_with(&["--loader", "emscripten"]).contains("#include <GLES3/gl3.h>\n"));
+
+    let desktop = warnings(|| { exec(&["--loader", "emscripten", "--profile", "core"]); });
+    assert_eq!(desktop, ["--loader emscripten with a desktop profile, functions WebGL lacks will be NULL"]);
+}
+
+#[test]
+fn extensions_from_stdin_keeps_the_listed_extensions() {
+    let stdin = &b"GL_ARB_debug_output\n# piped from another tool\nGL_KHR_debug\n"[..];
+    let mut filtered = exec(&[]);
+    filtered.extensions = Some(extension_list(stdin).unwrap());
+    assert_eq!(filtered.extensions.as_ref().unwrap().len(), 2);
+
+    let ids = ids(&filtered);
+    assert!(ids.contains(&"glDebugMessageControlARB".to_string()));
+    assert!(ids.contains(&"glDebugMessageCallbackARB".to_string()));
+    assert!(!ids.contains(&"glTextureBarrierNV".to_string()));
+    // Core functions belong to no extension
+    assert!(ids.contains(&"glClear".to_string()));
+}
+
+#[test]
+fn reloading_resolves_every_function_again() {
+    let body = |source: &str, signature: &str| {
+        let start = source.find(&format!("{}\n{{\n", signature)).unwrap();
+        let end = start + source[start..].find("\n}\n").unwrap();
+        source[start..end].to_string()
+    };
+
+    // Every call of the reload entry point goes through load_procs, which
+    // overwrites each pointer unconditionally
+    for &(args, reload) in &[(&[][..], "int gl3wInit(void)"),
+                             (&["--idempotent-init"][..], "int gl3wReinit(void)"),
+                             (&["--refcount-init"][..], "int gl3wReinit(void)")] {
+        let source = source_with(args);
+        let reload = body(&source, reload);
+        assert!(reload.contains("load();") || reload.contains("\tload_procs();\n"), "{:?}", args);
+        assert!(!reload.contains("return 0;"), "{:?}", args);
+
+        let load_procs = body(&source, "static void load_procs(void)");
+        assert!(!load_procs.contains("if ("));
+        for id in ids(&exec(args)) {
+            assert!(load_procs.contains(&format!(" get_proc(\"{}\");", id)), "{}", id);
+        }
+    }
+
+    // Only gl3wInit skips loading when the guard says it is done
+    assert!(body(&source_with(&["--idempotent-init"]), "int gl3wInit(void)").contains("\tif (loaded)\n\t\treturn 0;\n"));
+}
+
+#[test]
+fn stdout_header_writes_the_source_to_its_file() {
+    // The header goes to the test's stdout, so the run is made by a child
+    // process
+    if env::var("GL3W_NATIVE_TEST_CHILD").is_ok() {
+        run(&exec(&["--input", "glcorearb.h", "--stdout", "header", "--no-timestamp", "-q"])).unwrap();
+        return;
+    }
+
+    let dir = temp_dir("stdout-header");
+    fixture(&dir, "glcorearb.h", GLCOREARB_H);
+    let output = run_child("stdout_header_writes_the_source_to_its_file", &dir);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("#ifndef __gl3w_h_\n") && stdout.contains("extern PFNGLCLEARPROC"));
+    assert!(!stdout.contains("get_proc(\"glClear\")"));
+
+    assert!(!dir.join("src/gl3w.h").exists());
+    assert!(read(dir.join("src/gl3w.c")).contains(r#"get_proc("glClear")"#));
+}
+
+#[test]
+fn gles_extension_headers_are_merged() {
+    let gl3 = "\
+#ifndef GL_ES_VERSION_2_0
+#define GL_ES_VERSION_2_0 1
+typedef void (GL_APIENTRYP PFNGLCLEARPROC) (GLbitfield mask);
+typedef void (GL_APIENTRYP PFNGLGETINTEGERVPROC) (GLenum pname, GLint *data);
+#if GL_GLES_PROTOTYPES
+GL_APICALL void GL_APIENTRY glClear (GLbitfield mask);
+GL_APICALL void GL_APIENTRY glGetIntegerv (GLenum pname, GLint *data);
+#endif
+#endif /* GL_ES_VERSION_2_0 */
+";
+    let gl2ext = "\
+#ifndef GL_OES_vertex_array_object
+#define GL_OES_vertex_array_object 1
+typedef void (GL_APIENTRYP PFNGLBINDVERTEXARRAYOESPROC) (GLuint array);
+#ifdef GL_GLEXT_PROTOTYPES
+GL_APICALL void GL_APIENTRY glBindVertexArrayOES (GLuint array);
+#endif
+#endif /* GL_OES_vertex_array_object */
+";
+    let dir = temp_dir("gles-extra-header");
+    let gles = exec_in(&dir, &["--profile", "gles3", "--no-timestamp", "-q",
+                               "--extra-header", "https://registry.khronos.org/OpenGL/api/GLES2/gl2ext.h"]);
+    cache(&gles, gl3);
+    let path_gl2ext = gles.extra_header_paths().remove(0);
+    assert!(path_gl2ext.ends_with("include/GLES2/gl2ext.h"));
+    fixture(path_gl2ext.parent().unwrap(), "gl2ext.h", gl2ext);
+    run(&gles).unwrap();
+
+    let (header, source) = generated(&gles);
+    assert!(header.contains("#include <GLES3/gl3.h>\n#include <GLES2/gl2ext.h>\n"));
+    assert!(!header.contains("glcorearb.h"));
+    for &(pfn, var) in &[("PFNGLCLEARPROC", "gl3wClear"), ("PFNGLBINDVERTEXARRAYOESPROC", "gl3wBindVertexArrayOES")] {
+        assert_eq!(line_with(&header, &format!(" {};", var)), ["extern", pfn, &format!("{};", var)[..]]);
+    }
+    assert!(source.contains(r#"get_proc("glClear")"#) && source.contains(r#"get_proc("glBindVertexArrayOES")"#));
+}
+
+#[test]
+fn list_outputs_matches_the_written_files() {
+    fn files(dir: &Path, found: &mut Vec<PathBuf>) {
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                files(&path, found);
+            }
+            else {
+                found.push(path);
+            }
+        }
+    }
+
+    let dir = temp_dir("list-outputs");
+    let procs = dir.join("procs.json");
+    let args = ["--dump-procs", procs.to_str().unwrap(), "--no-timestamp", "-q"];
+
+    // Listing neither downloads nor writes anything
+    let mut list_args = args.to_vec();
+    list_args.push("--list-outputs");
+    run(&exec_in(&dir, &list_args)).unwrap();
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+    let exec = generate(&dir, &args);
+    let mut listed = exec.output_paths();
+    listed.sort();
+    let mut written = Vec::new();
+    files(&dir, &mut written);
+    written.retain(|path| *path != exec.path_glcorearb);
+    written.sort();
+    assert_eq!(listed, written);
+    assert_eq!(listed.len(), 3);
+}
+
+#[test]
+fn reproducible_runs_are_byte_identical() {
+    let dir = temp_dir("reproducible");
+    let (manifest, changelog) = (dir.join("gl3w.manifest"), dir.join("CHANGELOG-gl.md"));
+    let args = ["--manifest", manifest.to_str().unwrap(), "--changelog", changelog.to_str().unwrap(),
+                "--reproducible", "-q"];
+    let exec = exec_in(&dir, &args);
+    let changed = GLCOREARB_H.replace("glViewport ", "glViewportNV ").replace("VIEWPORTPROC", "VIEWPORTNVPROC");
+
+    // Generating, then again after the functions change for a changelog
+    // entry, gives the same files each time
+    let outputs = || {
+        let _ = fs::remove_file(&manifest);
+        let _ = fs::remove_file(&changelog);
+        cache(&exec, GLCOREARB_H);
+        run(&exec).unwrap();
+        cache(&exec, &changed);
+        run(&exec).unwrap();
+        (generated(&exec), read(&manifest), read(&changelog))
+    };
+    let first = outputs();
+    assert!((first.0).0.starts_with("/* Generated by gl3w-native ") && !(first.0).0.contains(" * date: "));
+    assert!(first.2.starts_with(&format!("## {}\n\n### Added\n", exec.source())));
+    assert_eq!(outputs(), first);
+
+    // SOURCE_DATE_EPOCH is recorded as given, whenever the run is
+    let mut dated = exec_in(&dir, &args);
+    dated.source_date_epoch = Some(parse_source_date_epoch("1700000000\n").unwrap());
+    run(&dated).unwrap();
+    assert!(generated(&dated).0.contains(" * date: 2023-11-14T22:13:20Z\n"));
+
+    for epoch in &["", "soon", "-1", "253402300800", "99999999999999999999"] {
+        assert_eq!(parse_source_date_epoch(epoch), Err(format!("invalid SOURCE_DATE_EPOCH `{}`", epoch)));
+    }
+}
+
+#[test]
+fn build_scripts_generate_into_out_dir() {
+    let dir = temp_dir("out-dir");
+    let out_dir = dir.join("out");
+    let mut exec = exec(&["--no-timestamp", "-q"]);
+    exec.path_glcorearb = dir.join(&exec.path_glcorearb);
+    cache(&exec, GLCOREARB_H);
+
+    let mut cargo = Vec::new();
+    let written = exec.generate_in(&out_dir, &mut cargo).unwrap();
+    assert_eq!(written, [out_dir.join("src/gl3w.h"), out_dir.join("src/gl3w.c")]);
+    assert!(!dir.join("src").exists());
+    assert!(read(&written[1]).contains(r#"get_proc("glClear")"#));
+    assert_eq!(String::from_utf8(cargo).unwrap(), format!("cargo:rerun-if-changed={}\n", exec.path_glcorearb.display()));
+}